@@ -15,10 +15,36 @@ pub struct Args {
 
     #[arg(short = 's', long, default_value_t = 25)]
     pub page_size: u8,
+
+    /// Rows loaded at a time from ad-hoc query results
+    #[arg(long, default_value_t = 500)]
+    pub fetch_size: usize,
+
+    /// Memory cap, in MiB, of a single ad-hoc result set
+    #[arg(long, default_value_t = 64)]
+    pub max_result_mb: usize,
+
+    /// Run every statement of a SQL script on startup
+    #[arg(short = 'e', long, value_name = "FILE")]
+    pub execute: Option<String>,
+
+    /// Keep running the script after a statement fails
+    #[arg(long, requires = "execute")]
+    pub continue_on_error: bool,
+
+    /// Open the session read-only and disable every editing feature
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Profiles file, `profiles.json` in the configuration directory by default
+    #[arg(long, value_name = "FILE")]
+    pub profiles: Option<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Command {
+    /// Connect as a profile of the profiles file says
+    Profile { name: String },
     Sqlite {
         #[arg[short, long]]
         filename: String,
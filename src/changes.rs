@@ -0,0 +1,118 @@
+use crate::edit::{Key, Locate, Statement, Value};
+
+/// A data edit staged in the grid, waiting to be committed.
+#[derive(Clone, Debug)]
+pub struct Change {
+    pub table: String,
+    pub statement: Statement,
+    pub kind: Kind,
+}
+
+#[derive(Clone, Debug)]
+pub enum Kind {
+    Update {
+        key: Key,
+        column: String,
+        old: String,
+        new: Value,
+    },
+    Insert {
+        values: Vec<(String, Value)>,
+        locate: Locate,
+    },
+    Delete {
+        keys: Vec<Key>,
+    },
+}
+
+/// Edits applied together, in order, once committed.
+#[derive(Default)]
+pub struct ChangeSet {
+    pub changes: Vec<Change>,
+}
+
+impl Change {
+    pub fn title(&self) -> String {
+        match &self.kind {
+            Kind::Update { key, .. } => format!("UPDATE {} ({})", self.table, key.shown()),
+            Kind::Insert { .. } => format!("INSERT {}", self.table),
+            Kind::Delete { keys } => format!("DELETE {} ({} row(s))", self.table, keys.len()),
+        }
+    }
+
+    /// Old values as `-` lines and new ones as `+` lines.
+    pub fn diff(&self) -> Vec<(char, String)> {
+        match &self.kind {
+            Kind::Update {
+                column, old, new, ..
+            } => vec![
+                ('-', format!("{}: {}", column, old)),
+                ('+', format!("{}: {}", column, new.shown())),
+            ],
+            Kind::Insert { values, .. } => values
+                .iter()
+                .map(|(column, value)| ('+', format!("{}: {}", column, value.shown())))
+                .collect(),
+            Kind::Delete { keys } => keys.iter().map(|key| ('-', key.shown())).collect(),
+        }
+    }
+}
+
+impl ChangeSet {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn push(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+
+    /// Drops the latest change, which nothing staged after it can depend on.
+    pub fn undo(&mut self) -> Option<Change> {
+        self.changes.pop()
+    }
+
+    pub fn statements(&self) -> Vec<Statement> {
+        self.changes
+            .iter()
+            .map(|change| change.statement.clone())
+            .collect()
+    }
+
+    /// The last insert, which is the one the database reports an id for.
+    pub fn last_insert(&self) -> Option<(String, Locate)> {
+        self.changes
+            .iter()
+            .rev()
+            .find_map(|change| match &change.kind {
+                Kind::Insert { locate, .. } => Some((change.table.clone(), locate.clone())),
+                _ => None,
+            })
+    }
+
+    /// Pending values of `table`'s cells, latest last, by row key and column.
+    pub fn edits(&self, table: &str) -> Vec<(Key, String, String)> {
+        self.changes
+            .iter()
+            .filter(|change| change.table == table)
+            .filter_map(|change| match &change.kind {
+                Kind::Update {
+                    key, column, new, ..
+                } => Some((key.clone(), column.clone(), new.shown())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Keys of `table`'s rows pending deletion.
+    pub fn deletions(&self, table: &str) -> Vec<Key> {
+        self.changes
+            .iter()
+            .filter(|change| change.table == table)
+            .flat_map(|change| match &change.kind {
+                Kind::Delete { keys } => keys.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,590 @@
+use crate::{
+    copy,
+    db::{self, Connection, Database, Dialect},
+    ddl::{self, ColumnDef, Operation},
+    schema::{EXPRESSION, ForeignKey, Index},
+};
+use color_eyre::{Result, eyre::eyre};
+use sqlx::{Row, mysql::MySqlRow, sqlite::SqliteRow};
+use std::path::Path;
+
+/// A table as compared: its columns, secondary indexes and foreign keys.
+pub struct TableDef {
+    pub name: String,
+    pub columns: Vec<ColumnDef>,
+    pub indexes: Vec<Index>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+/// The schema of one database.
+pub struct Catalog {
+    pub dialect: Dialect,
+    pub tables: Vec<TableDef>,
+    /// Views, with the query each one runs.
+    pub views: Vec<(String, String)>,
+}
+
+/// An object that differs between two schemas, as each of them has it.
+pub struct Difference {
+    pub object: String,
+    pub here: Option<String>,
+    pub there: Option<String>,
+}
+
+/// What comparing this database with another one found.
+pub struct Comparison {
+    /// The other database, without credentials.
+    pub other: String,
+    pub differences: Vec<Difference>,
+    /// Statements bringing the other database in line with this one.
+    pub to_other: Vec<String>,
+    /// Statements bringing this database in line with the other one.
+    pub to_this: Vec<String>,
+}
+
+/// One step of a migration.
+enum Step {
+    Operation(Operation),
+    Sql(String),
+    /// What the migration cannot do, left as a comment.
+    Note(String),
+}
+
+impl Connection {
+    /// Loads the tables, indexes, foreign keys and views of the database.
+    pub async fn catalog(&mut self) -> Result<Catalog> {
+        let views = self.views().await?;
+
+        let mut tables = Vec::new();
+        for name in self.tables().await? {
+            // MySQL lists views among the tables.
+            if views.iter().any(|(view, _)| *view == name) {
+                continue;
+            }
+            let columns = self.definitions(&name).await?;
+            let indexes = self
+                .indexes(&name)
+                .await?
+                .into_iter()
+                .filter(|index| !index.primary)
+                .collect();
+            let foreign_keys = self.foreign_keys(&name).await?;
+            tables.push(TableDef {
+                name,
+                columns,
+                indexes,
+                foreign_keys,
+            });
+        }
+
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Catalog {
+            dialect: self.dialect(),
+            tables,
+            views,
+        })
+    }
+
+    async fn views(&mut self) -> Result<Vec<(String, String)>> {
+        Ok(match self {
+            Self::SQLite(conn) => {
+                sqlx::query("SELECT name, sql FROM sqlite_schema WHERE type = 'view' ORDER BY name")
+                    .map(|row: SqliteRow| {
+                        let sql: String = row.get(1);
+                        (row.get(0), view_query(&sql))
+                    })
+                    .fetch_all(&mut **conn)
+                    .await?
+            }
+            Self::MySQL(conn) => {
+                let schema: Option<String> = sqlx::query_scalar("SELECT CAST(DATABASE() AS CHAR)")
+                    .fetch_one(&mut **conn)
+                    .await?;
+                // The server qualifies every name with the schema, which differs
+                // between databases.
+                let prefix = format!("`{}`.", schema.unwrap_or_default());
+
+                sqlx::query(
+                    "SELECT CAST(TABLE_NAME AS CHAR), CAST(VIEW_DEFINITION AS CHAR)
+                    FROM INFORMATION_SCHEMA.VIEWS
+                    WHERE TABLE_SCHEMA = DATABASE()
+                    ORDER BY TABLE_NAME",
+                )
+                .map(|row: MySqlRow| {
+                    let query: String = row.get(1);
+                    (row.get(0), query.replace(&prefix, ""))
+                })
+                .fetch_all(&mut **conn)
+                .await?
+            }
+        })
+    }
+}
+
+/// The query of a `CREATE VIEW` statement.
+fn view_query(sql: &str) -> String {
+    // ASCII uppercase keeps the byte offsets of `sql`.
+    let upper = sql.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+    let start = upper.match_indices("AS").find(|(idx, _)| {
+        let before = idx.checked_sub(1).map(|idx| bytes[idx]);
+        let after = bytes.get(idx + 2);
+        matches!(before, Some(b) if b.is_ascii_whitespace() || b"\")`]".contains(&b))
+            && matches!(after, Some(b) if b.is_ascii_whitespace() || *b == b'(')
+    });
+
+    match start {
+        Some((idx, _)) => sql[idx + 2..].trim().to_string(),
+        None => sql.to_string(),
+    }
+}
+
+/// Text compared regardless of case and spacing.
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn describe_column(column: &ColumnDef) -> String {
+    let mut text = column.data_type.clone();
+    if column.pk {
+        text.push_str(" PRIMARY KEY");
+    } else if !column.nullable {
+        text.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.default {
+        text.push_str(&format!(" DEFAULT {}", default));
+    }
+    if column.auto_increment {
+        text.push_str(" AUTO_INCREMENT");
+    }
+    text
+}
+
+fn describe_index(index: &Index) -> String {
+    let mut text = format!(
+        "{}({})",
+        if index.unique { "UNIQUE " } else { "" },
+        index.columns.join(", ")
+    );
+    if let Some(partial) = &index.partial {
+        text.push_str(&format!(" WHERE {}", partial));
+    }
+    text
+}
+
+fn describe_key(key: &ForeignKey) -> String {
+    format!("→ {} ({})", key.table, key.references.join(", "))
+}
+
+/// Indexes match by what they cover, whatever their names.
+fn same_index(a: &Index, b: &Index) -> bool {
+    a.unique == b.unique
+        && a.columns.len() == b.columns.len()
+        && a.columns
+            .iter()
+            .zip(&b.columns)
+            .all(|(a, b)| a.eq_ignore_ascii_case(b))
+        && a.partial.as_deref().map(normalize) == b.partial.as_deref().map(normalize)
+}
+
+fn same_key(a: &ForeignKey, b: &ForeignKey) -> bool {
+    let names = |a: &[String], b: &[String]| {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.eq_ignore_ascii_case(b))
+    };
+    names(&a.columns, &b.columns)
+        && a.table.eq_ignore_ascii_case(&b.table)
+        && names(&a.references, &b.references)
+        && a.on_update.eq_ignore_ascii_case(&b.on_update)
+        && a.on_delete.eq_ignore_ascii_case(&b.on_delete)
+}
+
+/// `FOREIGN KEY ... REFERENCES ...` as declared in `dialect`.
+fn reference(dialect: Dialect, key: &ForeignKey) -> String {
+    let list = |names: &[String]| {
+        names
+            .iter()
+            .map(|name| dialect.quote(name))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    let mut sql = format!(
+        "FOREIGN KEY ({}) REFERENCES {} ({})",
+        list(&key.columns),
+        dialect.quote(&key.table),
+        list(&key.references)
+    );
+    for (event, action) in [("UPDATE", &key.on_update), ("DELETE", &key.on_delete)] {
+        if !action.is_empty() && !action.eq_ignore_ascii_case("NO ACTION") {
+            sql.push_str(&format!(" ON {} {}", event, action));
+        }
+    }
+    sql
+}
+
+/// The columns `from` declares, as `to` would declare them. Columns that already
+/// match in `to` are taken from it unchanged; types match across dialects when
+/// either one maps to the other, and defaults are only compared within a dialect.
+fn desired_columns(
+    table: &str,
+    from: (Dialect, &[ColumnDef]),
+    to: (Dialect, &[ColumnDef]),
+) -> (Vec<ColumnDef>, Vec<Difference>, bool) {
+    let ((source, columns), (target, existing)) = (from, to);
+    let same = source == target;
+    let squash = |text: &str| text.replace(' ', "").to_uppercase();
+
+    let mut desired = Vec::new();
+    let mut differences = Vec::new();
+    let mut changed = false;
+    for column in columns {
+        let old = existing.iter().find(|old| old.name == column.name);
+        let equivalent = old.is_some_and(|old| {
+            let types = if same {
+                squash(&column.data_type) == squash(&old.data_type)
+            } else {
+                squash(&copy::map_type(
+                    source,
+                    target,
+                    &column.data_type,
+                    column.pk,
+                )) == squash(&old.data_type)
+                    || squash(&copy::map_type(target, source, &old.data_type, old.pk))
+                        == squash(&column.data_type)
+            };
+            types
+                && column.pk == old.pk
+                && (column.pk || column.nullable == old.nullable)
+                && (!same
+                    || (column.default == old.default
+                        && column.auto_increment == old.auto_increment))
+        });
+
+        match old {
+            Some(old) if equivalent => desired.push(old.clone()),
+            _ => {
+                changed = true;
+                differences.push(Difference {
+                    object: format!("column {}.{}", table, column.name),
+                    here: Some(describe_column(column)),
+                    there: old.map(describe_column),
+                });
+                desired.push(ColumnDef {
+                    data_type: copy::map_type(source, target, &column.data_type, column.pk),
+                    default: if same {
+                        column.default.clone()
+                    } else {
+                        old.and_then(|old| old.default.clone())
+                    },
+                    auto_increment: if same {
+                        column.auto_increment
+                    } else {
+                        old.map_or(column.auto_increment, |old| old.auto_increment)
+                    },
+                    ..column.clone()
+                });
+            }
+        }
+    }
+    for old in existing {
+        if columns.iter().all(|column| column.name != old.name) {
+            changed = true;
+            differences.push(Difference {
+                object: format!("column {}.{}", table, old.name),
+                here: None,
+                there: Some(describe_column(old)),
+            });
+        }
+    }
+
+    (desired, differences, changed)
+}
+
+/// The differences from `from` to `to`, and the steps bringing `to` in line with `from`.
+fn diff(from: &Catalog, to: &Catalog) -> (Vec<Difference>, Vec<Step>) {
+    let (source, target) = (from.dialect, to.dialect);
+    let q = |ident: &str| target.quote(ident);
+
+    let mut differences = Vec::new();
+    let (mut before, mut tables, mut indexes, mut keys, mut dropped, mut after) = (
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    );
+
+    // Views go first, as they may depend on the tables changing.
+    for (name, query) in &from.views {
+        match to.views.iter().find(|(view, _)| view == name) {
+            Some((_, other)) if normalize(other) == normalize(query) => continue,
+            Some((_, other)) => {
+                differences.push(Difference {
+                    object: format!("view {}", name),
+                    here: Some(query.clone()),
+                    there: Some(other.clone()),
+                });
+                before.push(Step::Sql(format!("DROP VIEW {}", q(name))));
+            }
+            None => differences.push(Difference {
+                object: format!("view {}", name),
+                here: Some(query.clone()),
+                there: None,
+            }),
+        }
+        after.push(match source == target {
+            true => Step::Sql(format!("CREATE VIEW {} AS {}", q(name), query)),
+            false => Step::Note(format!(
+                "View {} is written in another dialect: CREATE VIEW {} AS {}",
+                name,
+                name,
+                normalize(query)
+            )),
+        });
+    }
+    for (name, query) in &to.views {
+        if from.views.iter().all(|(view, _)| view != name) {
+            differences.push(Difference {
+                object: format!("view {}", name),
+                here: None,
+                there: Some(query.clone()),
+            });
+            before.push(Step::Sql(format!("DROP VIEW {}", q(name))));
+        }
+    }
+
+    for table in &from.tables {
+        let Some(other) = to.tables.iter().find(|other| other.name == table.name) else {
+            differences.push(Difference {
+                object: format!("table {}", table.name),
+                here: Some(format!("{} columns", table.columns.len())),
+                there: None,
+            });
+            let (columns, _, _) =
+                desired_columns(&table.name, (source, &table.columns), (target, &[]));
+            let mut sql = ddl::create_table(target, &table.name, &columns);
+            if !table.foreign_keys.is_empty() {
+                // Inside the closing parenthesis of the definitions.
+                sql.truncate(sql.len() - 2);
+                for key in &table.foreign_keys {
+                    sql.push_str(&format!(",\n  {}", reference(target, key)));
+                }
+                sql.push_str("\n)");
+            }
+            tables.push(Step::Sql(sql));
+            for index in &table.indexes {
+                indexes.push(create_index(from, target, &table.name, index));
+            }
+            continue;
+        };
+
+        let (columns, changes, changed) = desired_columns(
+            &table.name,
+            (source, &table.columns),
+            (target, &other.columns),
+        );
+        differences.extend(changes);
+        if changed {
+            tables.push(Step::Operation(Operation::Redefine {
+                table: table.name.clone(),
+                columns,
+            }));
+        }
+
+        for index in &table.indexes {
+            if !other.indexes.iter().any(|old| same_index(index, old)) {
+                differences.push(Difference {
+                    object: format!("index {}.{}", table.name, index.name),
+                    here: Some(describe_index(index)),
+                    there: None,
+                });
+                indexes.push(create_index(from, target, &table.name, index));
+            }
+        }
+        for old in &other.indexes {
+            if table.indexes.iter().any(|index| same_index(index, old)) {
+                continue;
+            }
+            differences.push(Difference {
+                object: format!("index {}.{}", table.name, old.name),
+                here: None,
+                there: Some(describe_index(old)),
+            });
+            // Dropped before the new ones, which may reuse the name.
+            indexes.insert(
+                0,
+                if old.name.starts_with("sqlite_autoindex_") {
+                    Step::Note(format!(
+                        "{} belongs to a UNIQUE constraint of {}, which only a rebuild drops.",
+                        old.name, table.name
+                    ))
+                } else {
+                    Step::Operation(Operation::DropIndex {
+                        table: table.name.clone(),
+                        name: old.name.clone(),
+                    })
+                },
+            );
+        }
+
+        for key in &table.foreign_keys {
+            if other.foreign_keys.iter().any(|old| same_key(key, old)) {
+                continue;
+            }
+            differences.push(Difference {
+                object: format!("foreign key {} ({})", table.name, key.columns.join(", ")),
+                here: Some(describe_key(key)),
+                there: None,
+            });
+            keys.push(match target {
+                Dialect::SQLite => Step::Note(format!(
+                    "SQLite cannot add a foreign key to {}: {}",
+                    table.name,
+                    reference(target, key)
+                )),
+                Dialect::MySQL => Step::Sql(format!(
+                    "ALTER TABLE {} ADD {}{}",
+                    q(&table.name),
+                    match source {
+                        Dialect::MySQL => format!("CONSTRAINT {} ", q(&key.name)),
+                        Dialect::SQLite => String::new(),
+                    },
+                    reference(target, key)
+                )),
+            });
+        }
+        for old in &other.foreign_keys {
+            if table.foreign_keys.iter().any(|key| same_key(key, old)) {
+                continue;
+            }
+            differences.push(Difference {
+                object: format!("foreign key {} ({})", table.name, old.columns.join(", ")),
+                here: None,
+                there: Some(describe_key(old)),
+            });
+            keys.insert(
+                0,
+                match target {
+                    Dialect::SQLite => Step::Note(format!(
+                        "SQLite cannot drop a foreign key from {}: {}",
+                        table.name,
+                        reference(target, old)
+                    )),
+                    Dialect::MySQL => Step::Sql(format!(
+                        "ALTER TABLE {} DROP FOREIGN KEY {}",
+                        q(&table.name),
+                        q(&old.name)
+                    )),
+                },
+            );
+        }
+    }
+    for other in &to.tables {
+        if from.tables.iter().all(|table| table.name != other.name) {
+            differences.push(Difference {
+                object: format!("table {}", other.name),
+                here: None,
+                there: Some(format!("{} columns", other.columns.len())),
+            });
+            dropped.push(Step::Operation(Operation::DropTable {
+                table: other.name.clone(),
+            }));
+        }
+    }
+
+    let steps = [before, tables, indexes, keys, dropped, after]
+        .into_iter()
+        .flatten()
+        .collect();
+    (differences, steps)
+}
+
+/// Creating `index` of `table` in `target`. Indexes on expressions or with a
+/// condition only carry over as the statement that created them, within SQLite.
+fn create_index(from: &Catalog, target: Dialect, table: &str, index: &Index) -> Step {
+    if index.partial.is_some() || index.columns.iter().any(|column| column == EXPRESSION) {
+        return match (&index.sql, from.dialect == target) {
+            (Some(sql), true) => Step::Sql(sql.clone()),
+            _ => Step::Note(format!(
+                "Index {} on {} {} has to be created by hand.",
+                index.name,
+                table,
+                describe_index(index)
+            )),
+        };
+    }
+
+    Step::Operation(Operation::CreateIndex {
+        table: table.into(),
+        // SQLite names the indexes of UNIQUE constraints itself.
+        name: if index.name.starts_with("sqlite_autoindex_") {
+            format!("{}_{}_unique", table, index.columns.join("_"))
+        } else {
+            index.name.clone()
+        },
+        columns: index.columns.clone(),
+        unique: index.unique,
+    })
+}
+
+/// The statements carrying out `steps` on `conn`.
+async fn migration(conn: &mut Connection, steps: Vec<Step>) -> Result<Vec<String>> {
+    let mut statements = Vec::new();
+    for step in steps {
+        match step {
+            Step::Operation(operation) => match conn.ddl(&operation, false).await {
+                Ok(sql) => statements.extend(sql),
+                // A rebuild that would lose a constraint is left to be made by hand.
+                Err(err) if matches!(operation, Operation::Redefine { .. }) => {
+                    let note = err
+                        .to_string()
+                        .split_whitespace()
+                        .collect::<Vec<&str>>()
+                        .join(" ");
+                    statements.push(format!("-- {}", note))
+                }
+                Err(err) => return Err(err),
+            },
+            Step::Sql(sql) => statements.push(sql),
+            Step::Note(note) => statements.push(format!("-- {}", note)),
+        }
+    }
+
+    // Tables may be created and dropped before the ones they reference.
+    if conn.dialect() == Dialect::MySQL && statements.iter().any(|sql| !sql.starts_with("--")) {
+        statements.insert(0, "SET FOREIGN_KEY_CHECKS = 0".into());
+        statements.push("SET FOREIGN_KEY_CHECKS = 1".into());
+    }
+
+    Ok(statements)
+}
+
+/// Compares the schema of `conn` with that of `target`, a SQLite file or a MySQL
+/// URL, and writes the migrations both ways.
+pub async fn compare(conn: &mut Connection, target: &str) -> Result<Comparison> {
+    // Opening a SQLite file creates it when missing.
+    if !target.contains("://") && !Path::new(target).exists() {
+        return Err(eyre!("{} does not exist.", target));
+    }
+
+    let database = Database::open(target).await?;
+    let mut other = database.acquire().await?;
+    let here = conn.catalog().await?;
+    let there = other.catalog().await?;
+
+    let (differences, steps) = diff(&here, &there);
+    let to_other = migration(&mut other, steps).await?;
+    let (_, steps) = diff(&there, &here);
+    let to_this = migration(conn, steps).await?;
+
+    Ok(Comparison {
+        other: db::without_credentials(target),
+        differences,
+        to_other,
+        to_this,
+    })
+}
@@ -0,0 +1,493 @@
+use crate::{
+    db::{self, Connection, Database, Dialect, Outcome},
+    ddl::{self, ColumnDef},
+    schema,
+};
+use color_eyre::{Result, eyre::eyre};
+use futures_util::TryStreamExt;
+use sqlx::{Row, TypeInfo, Value, ValueRef, mysql::MySqlRow, sqlite::SqliteRow};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Rows copied in one statement within a database, or inserted at most at a time
+/// into another one.
+const CHUNK: u64 = 1000;
+
+/// SQLite's historical limit on the parameters of a statement.
+const MAX_PARAMS: usize = 999;
+
+/// Copying `table` into a new table `name`, here or in the `target` database.
+#[derive(Clone, Debug)]
+pub struct Copy {
+    pub table: String,
+    /// A `mysql://` URL or a SQLite file; this database when unset.
+    pub target: Option<String>,
+    pub name: String,
+    /// Whether the rows are copied too, or only the structure.
+    pub rows: bool,
+}
+
+/// Rows copied so far out of the total, shared with the status bar.
+#[derive(Clone, Default)]
+pub struct Progress(Arc<(AtomicU64, AtomicU64)>);
+
+/// A value carried over as is.
+enum Datum {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl Copy {
+    /// Where the copy went, without the credentials of a URL.
+    pub fn destination(&self) -> String {
+        match &self.target {
+            Some(target) => db::without_credentials(target),
+            None => "this database".into(),
+        }
+    }
+}
+
+impl Progress {
+    pub fn get(&self) -> (u64, u64) {
+        (
+            self.0.0.load(Ordering::Relaxed),
+            self.0.1.load(Ordering::Relaxed),
+        )
+    }
+
+    fn start(&self, total: u64) {
+        self.0.0.store(0, Ordering::Relaxed);
+        self.0.1.store(total, Ordering::Relaxed);
+    }
+
+    fn advance(&self, rows: u64) {
+        self.0.0.fetch_add(rows, Ordering::Relaxed);
+    }
+}
+
+/// The type of a column, coming from `from`, as declared in `to`; `key` tells
+/// whether the column is part of the primary key.
+pub fn map_type(from: Dialect, to: Dialect, declared: &str, key: bool) -> String {
+    let data_type = declared.to_uppercase();
+    let has = |part: &str| data_type.contains(part);
+
+    match (from, to) {
+        (Dialect::SQLite, Dialect::SQLite) | (Dialect::MySQL, Dialect::MySQL) => declared.into(),
+        (Dialect::MySQL, Dialect::SQLite) => if has("INT") || has("BIT") || has("YEAR") {
+            "INTEGER"
+        } else if has("FLOAT") || has("DOUBLE") || has("REAL") {
+            "REAL"
+        } else if has("DECIMAL") || has("NUMERIC") {
+            "NUMERIC"
+        } else if has("BLOB") || has("BINARY") {
+            "BLOB"
+        } else {
+            "TEXT"
+        }
+        .into(),
+        // SQLite's type affinity rules, with keys kept short enough to be indexed.
+        (Dialect::SQLite, Dialect::MySQL) => {
+            if has("INT") {
+                "BIGINT".into()
+            } else if has("CHAR") || has("CLOB") || has("TEXT") {
+                if key || data_type.starts_with("VARCHAR(") {
+                    "VARCHAR(255)".into()
+                } else {
+                    "LONGTEXT".into()
+                }
+            } else if data_type.is_empty() || has("BLOB") {
+                if key {
+                    "VARBINARY(255)".into()
+                } else {
+                    "LONGBLOB".into()
+                }
+            } else if has("REAL") || has("FLOA") || has("DOUB") {
+                "DOUBLE".into()
+            } else if data_type.starts_with("DATE") || data_type.starts_with("TIME") {
+                declared.into()
+            } else {
+                "DECIMAL(65, 30)".into()
+            }
+        }
+    }
+}
+
+fn sqlite_datum(row: &SqliteRow, idx: usize) -> Result<Datum> {
+    let value = row.try_get_raw(idx)?.to_owned();
+    if value.is_null() {
+        return Ok(Datum::Null);
+    }
+
+    Ok(match value.type_info().name() {
+        "INTEGER" => Datum::Integer(value.try_decode()?),
+        "REAL" => Datum::Real(value.try_decode()?),
+        "BLOB" => Datum::Blob(value.try_decode()?),
+        _ => Datum::Text(value.try_decode()?),
+    })
+}
+
+/// Reads a value sent as text, which the target converts back by its column type.
+fn mysql_datum(row: &MySqlRow, idx: usize) -> Result<Datum> {
+    let value = ValueRef::to_owned(&row.try_get_raw(idx)?);
+    if value.is_null() {
+        return Ok(Datum::Null);
+    }
+
+    let name = value.type_info().name().to_string();
+    let bytes: Vec<u8> = value.try_decode_unchecked()?;
+
+    Ok(if name.contains("BLOB") || name.contains("BINARY") {
+        Datum::Blob(bytes)
+    } else {
+        Datum::Text(String::from_utf8_lossy(&bytes).into_owned())
+    })
+}
+
+/// `sql`, the statement that created an index, made for `table` under `name`.
+fn copy_index(sql: &str, unique: bool, name: &str, table: &str) -> Option<String> {
+    let dialect = Dialect::SQLite;
+    let columns = sql.find('(')?;
+    Some(format!(
+        "CREATE {}INDEX {} ON {} {}",
+        if unique { "UNIQUE " } else { "" },
+        dialect.quote(name),
+        dialect.quote(table),
+        &sql[columns..]
+    ))
+}
+
+impl Connection {
+    /// Copies a table's structure, and its rows unless only the structure is asked
+    /// for, reporting how many rows are done through `progress`.
+    ///
+    /// Returns the number of rows copied.
+    pub async fn copy_table(
+        &mut self,
+        copy: &Copy,
+        nested: bool,
+        progress: &Progress,
+    ) -> Result<u64> {
+        match &copy.target {
+            Some(target) => {
+                let db = Database::open(target).await?;
+                let mut target = db.acquire().await?;
+                self.copy_into(&mut target, copy, progress).await
+            }
+            None => self.duplicate(copy, nested, progress).await,
+        }
+    }
+
+    /// Copies a table within this database with its indexes and constraints, a
+    /// chunk of rows per statement.
+    async fn duplicate(&mut self, copy: &Copy, nested: bool, progress: &Progress) -> Result<u64> {
+        let dialect = self.dialect();
+        let (table, name) = (dialect.quote(&copy.table), dialect.quote(&copy.name));
+
+        match dialect {
+            Dialect::SQLite => {
+                let (original, _) = self.creation(&copy.table).await?;
+                let current = self.definitions(&copy.table).await?;
+                let mut statements = vec![ddl::redefine(
+                    &copy.table,
+                    &original,
+                    &copy.name,
+                    &current,
+                    &current,
+                )?];
+                // Index names are shared by the whole database.
+                statements.extend(self.indexes(&copy.table).await?.iter().filter_map(|index| {
+                    copy_index(
+                        index.sql.as_deref()?,
+                        index.unique,
+                        &format!("{}_{}", copy.name, index.name),
+                        &copy.name,
+                    )
+                }));
+                self.alter(&statements).await?;
+            }
+            // MySQL commits the open transaction before creating a table.
+            Dialect::MySQL if nested => {
+                return Err(eyre!(
+                    "Copying a table would commit the open transaction: commit or roll back first."
+                ));
+            }
+            Dialect::MySQL => {
+                self.execute(&format!("CREATE TABLE {} LIKE {}", name, table))
+                    .await?;
+            }
+        }
+
+        if !copy.rows {
+            return Ok(0);
+        }
+
+        let total = self.count(&copy.table, None).await?;
+        progress.start(total);
+
+        // Generated columns are left to the copy to compute.
+        let columns = self.columns(&copy.table).await?;
+        let key: Vec<String> = schema::primary_key(&columns)
+            .iter()
+            .map(|column| dialect.quote(column))
+            .collect();
+        let mut names: Vec<String> = columns
+            .iter()
+            .map(|column| dialect.quote(&column.name))
+            .collect();
+
+        // Each chunk starts after the last key copied, which is the highest in the
+        // copy; rowids are copied along to serve as the key.
+        let key = if !key.is_empty() {
+            Some(key)
+        } else if self.has_rowid(&copy.table).await? {
+            names.insert(0, "rowid".into());
+            Some(vec!["rowid".into()])
+        } else {
+            None
+        };
+        let names = names.join(", ");
+        let insert = format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {}",
+            name, names, names, table
+        );
+
+        if !nested {
+            self.execute("BEGIN").await?;
+        }
+        let mut copied = 0;
+        loop {
+            let statement = match &key {
+                Some(key) if copied == 0 => {
+                    format!("{} ORDER BY {} LIMIT {}", insert, key.join(", "), CHUNK)
+                }
+                Some(key) => format!(
+                    "{} WHERE ({}) > (SELECT {} FROM {} ORDER BY {} LIMIT 1) ORDER BY {} LIMIT {}",
+                    insert,
+                    key.join(", "),
+                    key.join(", "),
+                    name,
+                    key.iter()
+                        .map(|column| format!("{} DESC", column))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    key.join(", "),
+                    CHUNK
+                ),
+                None => insert.clone(),
+            };
+
+            match self.execute(&statement).await {
+                Ok(outcome) => {
+                    let rows = match outcome {
+                        Outcome::Affected(rows) => rows,
+                        Outcome::Rows(_) => 0,
+                    };
+                    copied += rows;
+                    progress.advance(rows);
+
+                    if key.is_none() || rows < CHUNK {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    if !nested {
+                        let _ = self.execute("ROLLBACK").await;
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        if !nested {
+            self.execute("COMMIT").await?;
+        }
+
+        Ok(copied)
+    }
+
+    /// Copies a table into another database, mapping the column types to its dialect.
+    /// Defaults only carry over between SQLite databases.
+    async fn copy_into(
+        &mut self,
+        target: &mut Connection,
+        copy: &Copy,
+        progress: &Progress,
+    ) -> Result<u64> {
+        let (from, to) = (self.dialect(), target.dialect());
+        let columns = self.columns(&copy.table).await?;
+        let definitions: Vec<ColumnDef> = self
+            .definitions(&copy.table)
+            .await?
+            .into_iter()
+            .zip(&columns)
+            .map(|(definition, column)| ColumnDef {
+                data_type: map_type(from, to, &column.data_type, column.pk > 0),
+                default: definition
+                    .default
+                    .filter(|_| from == Dialect::SQLite && to == Dialect::SQLite),
+                auto_increment: definition.auto_increment && from == to,
+                ..definition
+            })
+            .collect();
+
+        target
+            .execute(&ddl::create_table(to, &copy.name, &definitions))
+            .await?;
+
+        if !copy.rows {
+            return Ok(0);
+        }
+
+        progress.start(self.count(&copy.table, None).await?);
+
+        target.execute("BEGIN").await?;
+        match self.transfer(target, copy, columns.len(), progress).await {
+            Ok(copied) => {
+                target.execute("COMMIT").await?;
+                Ok(copied)
+            }
+            Err(err) => {
+                let _ = target.execute("ROLLBACK").await;
+                Err(err)
+            }
+        }
+    }
+
+    /// Streams the rows of the source table into `target`, a batch per `INSERT`.
+    async fn transfer(
+        &mut self,
+        target: &mut Connection,
+        copy: &Copy,
+        width: usize,
+        progress: &Progress,
+    ) -> Result<u64> {
+        let sql = format!("SELECT * FROM {}", self.dialect().quote(&copy.table));
+        let batch = (MAX_PARAMS / width.max(1)).clamp(1, CHUNK as usize);
+        let mut rows: Vec<Vec<Datum>> = Vec::new();
+        let mut copied = 0;
+
+        match self {
+            Self::SQLite(conn) => {
+                let mut stream = sqlx::query(&sql).fetch(&mut **conn);
+                while let Some(row) = stream.try_next().await? {
+                    rows.push(
+                        (0..row.len())
+                            .map(|idx| sqlite_datum(&row, idx))
+                            .collect::<Result<Vec<Datum>>>()?,
+                    );
+                    if rows.len() == batch {
+                        copied += target.insert_rows(&copy.name, width, &rows).await?;
+                        progress.advance(rows.len() as u64);
+                        rows.clear();
+                    }
+                }
+            }
+            // The text protocol sends every value as text, whatever its type.
+            Self::MySQL(conn) => {
+                let mut stream = sqlx::raw_sql(&sql).fetch(&mut **conn);
+                while let Some(row) = stream.try_next().await? {
+                    rows.push(
+                        (0..row.len())
+                            .map(|idx| mysql_datum(&row, idx))
+                            .collect::<Result<Vec<Datum>>>()?,
+                    );
+                    if rows.len() == batch {
+                        copied += target.insert_rows(&copy.name, width, &rows).await?;
+                        progress.advance(rows.len() as u64);
+                        rows.clear();
+                    }
+                }
+            }
+        }
+
+        if !rows.is_empty() {
+            copied += target.insert_rows(&copy.name, width, &rows).await?;
+            progress.advance(rows.len() as u64);
+        }
+
+        Ok(copied)
+    }
+
+    async fn insert_rows(&mut self, table: &str, width: usize, rows: &[Vec<Datum>]) -> Result<u64> {
+        let row = format!("({})", vec!["?"; width].join(", "));
+        let sql = format!(
+            "INSERT INTO {} VALUES {}",
+            self.dialect().quote(table),
+            vec![row.as_str(); rows.len()].join(", ")
+        );
+
+        Ok(match self {
+            Self::SQLite(conn) => {
+                let mut query = sqlx::query(&sql);
+                for datum in rows.iter().flatten() {
+                    query = match datum {
+                        Datum::Null => query.bind(None::<String>),
+                        Datum::Integer(value) => query.bind(*value),
+                        Datum::Real(value) => query.bind(*value),
+                        Datum::Text(value) => query.bind(value.as_str()),
+                        Datum::Blob(value) => query.bind(value.as_slice()),
+                    };
+                }
+                query.execute(&mut **conn).await?.rows_affected()
+            }
+            Self::MySQL(conn) => {
+                let mut query = sqlx::query(&sql);
+                for datum in rows.iter().flatten() {
+                    query = match datum {
+                        Datum::Null => query.bind(None::<String>),
+                        Datum::Integer(value) => query.bind(*value),
+                        Datum::Real(value) => query.bind(*value),
+                        Datum::Text(value) => query.bind(value.as_str()),
+                        Datum::Blob(value) => query.bind(value.as_slice()),
+                    };
+                }
+                query.execute(&mut **conn).await?.rows_affected()
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_type_keeps_types_within_a_dialect() {
+        for dialect in [Dialect::SQLite, Dialect::MySQL] {
+            assert_eq!(
+                map_type(dialect, dialect, "varchar(12)", false),
+                "varchar(12)"
+            );
+        }
+    }
+
+    #[test]
+    fn map_type_follows_sqlite_affinity_into_mysql() {
+        let map = |declared, key| map_type(Dialect::SQLite, Dialect::MySQL, declared, key);
+        assert_eq!(map("integer", false), "BIGINT");
+        assert_eq!(map("TEXT", false), "LONGTEXT");
+        assert_eq!(map("TEXT", true), "VARCHAR(255)");
+        assert_eq!(map("varchar(40)", false), "VARCHAR(255)");
+        assert_eq!(map("", false), "LONGBLOB");
+        assert_eq!(map("BLOB", true), "VARBINARY(255)");
+        assert_eq!(map("DOUBLE PRECISION", false), "DOUBLE");
+        assert_eq!(map("DATETIME", false), "DATETIME");
+        assert_eq!(map("NUMERIC", false), "DECIMAL(65, 30)");
+    }
+
+    #[test]
+    fn map_type_reduces_mysql_types_to_sqlite_affinities() {
+        let map = |declared| map_type(Dialect::MySQL, Dialect::SQLite, declared, false);
+        assert_eq!(map("INT UNSIGNED"), "INTEGER");
+        assert_eq!(map("tinyint(1)"), "INTEGER");
+        assert_eq!(map("YEAR"), "INTEGER");
+        assert_eq!(map("DOUBLE"), "REAL");
+        assert_eq!(map("DECIMAL(10,2)"), "NUMERIC");
+        assert_eq!(map("VARBINARY(16)"), "BLOB");
+        assert_eq!(map("DATETIME"), "TEXT");
+    }
+}
@@ -0,0 +1,450 @@
+use crate::{
+    db::{self, Connection, Database, Dialect, Item, Values},
+    edit::{self, Key, Value},
+    schema::{self, Column},
+};
+use color_eyre::{Result, eyre::eyre};
+use futures_util::{TryStreamExt, stream::BoxStream};
+use std::{cmp::Ordering, collections::BTreeSet, path::Path};
+
+/// What the grid shows for values it could not decode.
+const UNREADABLE: [&str; 2] = ["<err>", "<unsupported>"];
+
+/// A row that differs between the two tables: on one side only, or on both with
+/// different values.
+pub struct RowDiff {
+    pub key: Key,
+    pub here: Option<Values>,
+    pub there: Option<Values>,
+    /// Columns whose values differ, for a row on both sides.
+    changed: Vec<usize>,
+}
+
+/// The rows of a table compared with those of another one, matched by primary key.
+pub struct DataDiff {
+    pub table: String,
+    /// The table compared with, and the database holding it when not this one.
+    pub other: String,
+    /// The columns both tables have, binary and unreadable ones left out.
+    pub columns: Vec<String>,
+    pub rows: Vec<RowDiff>,
+    /// Rows equal on both sides.
+    pub same: usize,
+    /// Statements bringing the other table in line with this one.
+    pub sync: Vec<String>,
+    /// Whether the comparison stopped at the memory cap, before the last rows.
+    pub capped: bool,
+}
+
+/// One table, read in key order.
+struct Side<'c> {
+    table: &'c str,
+    rows: BoxStream<'c, Result<Item>>,
+    current: Option<Values>,
+}
+
+impl RowDiff {
+    /// Whether the value of column `idx` differs, or the row is on one side only.
+    pub fn differs(&self, idx: usize) -> bool {
+        match (&self.here, &self.there) {
+            (Some(_), Some(_)) => self.changed.contains(&idx),
+            _ => true,
+        }
+    }
+}
+
+impl DataDiff {
+    /// How many rows are only here, only there, and changed.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.rows
+            .iter()
+            .fold((0, 0, 0), |(here, there, changed), row| {
+                match (&row.here, &row.there) {
+                    (Some(_), None) => (here + 1, there, changed),
+                    (None, Some(_)) => (here, there + 1, changed),
+                    _ => (here, there, changed + 1),
+                }
+            })
+    }
+}
+
+impl Side<'_> {
+    /// Takes the current row and reads the next one, which has to come after it.
+    async fn take(&mut self, key: &[usize], numeric: &[bool]) -> Result<Option<Values>> {
+        let taken = self.current.take();
+        self.current = loop {
+            match self.rows.try_next().await? {
+                Some(Item::Row(_, values)) => break Some(values),
+                Some(Item::Affected(_)) => (),
+                None => break None,
+            }
+        };
+
+        if let Some(next) = &self.current {
+            if key.iter().any(|&idx| undecoded(&next[idx])) {
+                return Err(eyre!("A key of {} could not be read.", self.table));
+            }
+            if let Some(taken) = &taken
+                && compare_keys(&values(taken, key), &values(next, key), numeric) != Ordering::Less
+            {
+                return Err(eyre!(
+                    "The rows of {} do not come in key order; is the key typed alike on both sides?",
+                    self.table
+                ));
+            }
+        }
+
+        Ok(taken)
+    }
+}
+
+/// Values are both NULL, equal as text, or in a numeric column equal as numbers
+/// written differently (`1.5` and `1.50`).
+fn equal(a: &Option<String>, b: &Option<String>, numeric: bool) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a == b
+                || numeric
+                    && matches!(
+                        (a.parse::<f64>(), b.parse::<f64>()),
+                        (Ok(a), Ok(b)) if a == b
+                    )
+        }
+        (a, b) => a == b,
+    }
+}
+
+/// Orders keys as the queries sort them: NULL first, then by value in numeric
+/// columns and byte by byte in others.
+fn compare_keys(a: &[Option<String>], b: &[Option<String>], numeric: &[bool]) -> Ordering {
+    for ((a, b), numeric) in a.iter().zip(b).zip(numeric) {
+        let (a, b) = match (a, b) {
+            (Some(a), Some(b)) => (a, b),
+            (a, b) => match a.is_some().cmp(&b.is_some()) {
+                Ordering::Equal => continue,
+                order => return order,
+            },
+        };
+        let order = match (a.parse::<i128>(), b.parse::<i128>()) {
+            (Ok(x), Ok(y)) if *numeric => x.cmp(&y),
+            _ => match (a.parse::<f64>(), b.parse::<f64>()) {
+                (Ok(x), Ok(y)) if *numeric => x.total_cmp(&y),
+                _ => a.cmp(b),
+            },
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    Ordering::Equal
+}
+
+/// The values of `row` at `positions`.
+fn values<T: Clone>(row: &[T], positions: &[usize]) -> Vec<T> {
+    positions.iter().map(|&idx| row[idx].clone()).collect()
+}
+
+/// Whether the value is one the grid could not decode.
+fn undecoded(value: &Option<String>) -> bool {
+    value
+        .as_deref()
+        .is_some_and(|text| UNREADABLE.contains(&text))
+}
+
+fn value(value: &Option<String>) -> Value {
+    value.clone().map_or(Value::Null, Value::Text)
+}
+
+fn is_binary(column: &Column) -> bool {
+    let data_type = column.data_type.to_uppercase();
+    data_type.contains("BLOB") || data_type.contains("BINARY")
+}
+
+fn is_numeric(column: &Column) -> bool {
+    let data_type = column.data_type.to_uppercase();
+    (data_type.contains("INT") && !data_type.contains("POINT"))
+        || ["REAL", "FLOA", "DOUB", "DEC", "NUMERIC"]
+            .iter()
+            .any(|name| data_type.contains(name))
+}
+
+/// Selects `columns` of `table` in the order of `key`, text compared byte by byte.
+fn ordered(
+    dialect: Dialect,
+    table: &str,
+    columns: &[String],
+    key: &[&str],
+    numeric: &[bool],
+) -> String {
+    let order: Vec<String> = key
+        .iter()
+        .zip(numeric)
+        .map(|(column, numeric)| match (numeric, dialect) {
+            (true, _) => dialect.quote(column),
+            (false, Dialect::SQLite) => format!("{} COLLATE BINARY", dialect.quote(column)),
+            (false, Dialect::MySQL) => format!("CAST({} AS BINARY)", dialect.quote(column)),
+        })
+        .collect();
+
+    format!(
+        "SELECT {} FROM {} ORDER BY {}",
+        columns
+            .iter()
+            .map(|column| dialect.quote(column))
+            .collect::<Vec<String>>()
+            .join(", "),
+        dialect.quote(table),
+        order.join(", ")
+    )
+}
+
+/// Compares the rows of `table` with those of `other`, in the `target` database
+/// (a SQLite file or a MySQL URL) or in this one, reached through `pool`. Both
+/// tables are read in key order and merged as they come, until the rows that
+/// differ hold `max_bytes`.
+pub async fn data_diff(
+    conn: &mut Connection,
+    pool: &Database,
+    table: &str,
+    target: Option<&str>,
+    other: &str,
+    max_bytes: usize,
+) -> Result<DataDiff> {
+    let database = match target {
+        // Opening a SQLite file creates it when missing.
+        Some(target) if !target.contains("://") && !Path::new(target).exists() => {
+            return Err(eyre!("{} does not exist.", target));
+        }
+        Some(target) => Database::open(target).await?,
+        // Both tables are read at once, so the other one on a connection of its own.
+        None => pool.clone(),
+    };
+    let mut opened = database.acquire().await.map_err(|err| match target {
+        Some(_) => err,
+        None => eyre!(
+            "Comparing two tables of this database needs a second connection: {}",
+            err
+        ),
+    })?;
+
+    let columns = conn.columns(table).await?;
+    let key = schema::primary_key(&columns);
+    if key.is_empty() {
+        return Err(eyre!("{} has no primary key to match rows by.", table));
+    }
+
+    let theirs = opened.columns(other).await?;
+    if theirs.is_empty() {
+        return Err(eyre!("There is no table {} to compare with.", other));
+    }
+    if let Some(missing) = key
+        .iter()
+        .find(|name| theirs.iter().all(|column| column.name != **name))
+    {
+        return Err(eyre!(
+            "{} has no {} column to match rows by.",
+            other,
+            missing
+        ));
+    }
+
+    // The other table's columns that this one has too, in this table's order, and
+    // whether this table holds numbers in them.
+    let (shared, numeric): (Vec<Column>, Vec<bool>) = columns
+        .iter()
+        .filter(|column| !is_binary(column))
+        .filter_map(|column| {
+            theirs
+                .iter()
+                .find(|theirs| theirs.name == column.name && !is_binary(theirs))
+                .map(|theirs| (theirs.clone(), is_numeric(column)))
+        })
+        .unzip();
+    let names: Vec<String> = shared.iter().map(|column| column.name.clone()).collect();
+    let positions: Vec<usize> = key
+        .iter()
+        .map(|name| names.iter().position(|column| column == name))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or_else(|| eyre!("The primary key of {} cannot be compared.", table))?;
+    let key_numeric: Vec<bool> = positions.iter().map(|&idx| numeric[idx]).collect();
+    let key_names: Vec<&str> = key.iter().map(String::as_str).collect();
+
+    let dialect = opened.dialect();
+    let sql_here = ordered(conn.dialect(), table, &names, &key_names, &key_numeric);
+    let sql_there = ordered(dialect, other, &names, &key_names, &key_numeric);
+    let mut here = Side {
+        table,
+        rows: conn.stream(&sql_here),
+        current: None,
+    };
+    let mut there = Side {
+        table: other,
+        rows: opened.stream(&sql_there),
+        current: None,
+    };
+    here.take(&positions, &key_numeric).await?;
+    there.take(&positions, &key_numeric).await?;
+
+    let key_of = |row: &Values| Key {
+        columns: key.clone(),
+        values: values(row, &positions),
+    };
+    // Columns holding a value that could not be decoded, left out at the end.
+    let mut unreadable = BTreeSet::new();
+    let mut rows = Vec::new();
+    let mut same = 0;
+    let mut bytes = 0;
+    let mut capped = false;
+    loop {
+        let order = match (&here.current, &there.current) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => {
+                compare_keys(&values(a, &positions), &values(b, &positions), &key_numeric)
+            }
+        };
+        let (row_here, row_there) = match order {
+            Ordering::Less => (here.take(&positions, &key_numeric).await?, None),
+            Ordering::Greater => (None, there.take(&positions, &key_numeric).await?),
+            Ordering::Equal => (
+                here.take(&positions, &key_numeric).await?,
+                there.take(&positions, &key_numeric).await?,
+            ),
+        };
+
+        for row in row_here.iter().chain(&row_there) {
+            unreadable.extend(
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, value)| undecoded(value))
+                    .map(|(idx, _)| idx),
+            );
+        }
+        if let (Some(a), Some(b)) = (&row_here, &row_there)
+            && (0..names.len())
+                .all(|idx| unreadable.contains(&idx) || equal(&a[idx], &b[idx], numeric[idx]))
+        {
+            same += 1;
+            continue;
+        }
+
+        let row = row_here.as_ref().or(row_there.as_ref()).unwrap();
+        bytes += row_here
+            .iter()
+            .chain(&row_there)
+            .flatten()
+            .flatten()
+            .map(String::len)
+            .sum::<usize>();
+        rows.push(RowDiff {
+            key: key_of(row),
+            here: row_here,
+            there: row_there,
+            changed: Vec::new(),
+        });
+        if bytes >= max_bytes {
+            capped = true;
+            break;
+        }
+    }
+    drop(here);
+    drop(there);
+
+    // Columns that could not be read are left out, as binary ones are.
+    let kept: Vec<usize> = (0..names.len())
+        .filter(|idx| !unreadable.contains(idx))
+        .collect();
+    let pick = |row: Values| values(&row, &kept);
+    let rows: Vec<RowDiff> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let (here, there) = (row.here.map(pick), row.there.map(pick));
+            let changed: Vec<usize> = match (&here, &there) {
+                (Some(a), Some(b)) => (0..kept.len())
+                    .filter(|&idx| !equal(&a[idx], &b[idx], numeric[kept[idx]]))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            match (&here, &there) {
+                (Some(_), Some(_)) if changed.is_empty() => {
+                    same += 1;
+                    None
+                }
+                _ => Some(RowDiff {
+                    key: row.key,
+                    here,
+                    there,
+                    changed,
+                }),
+            }
+        })
+        .collect();
+    let shared: Vec<Column> = kept.iter().map(|&idx| shared[idx].clone()).collect();
+    let left_out = unreadable
+        .iter()
+        .map(|&idx| names[idx].as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let names = values(&names, &kept);
+
+    // Deletions first, so that inserted rows do not clash with them on a unique key.
+    let extra: Vec<Key> = rows
+        .iter()
+        .filter(|row| row.here.is_none())
+        .map(|row| row.key.clone())
+        .collect();
+    let mut sync: Vec<String> = edit::deletes(dialect, other, &extra)
+        .into_iter()
+        .map(|statement| statement.preview)
+        .collect();
+    for row in &rows {
+        match (&row.here, &row.there) {
+            (Some(here), Some(_)) => sync.extend(row.changed.iter().map(|&idx| {
+                edit::update(dialect, other, &row.key, &shared[idx], value(&here[idx])).preview
+            })),
+            (Some(here), None) => sync.push(
+                edit::insert(
+                    dialect,
+                    other,
+                    &shared,
+                    &here.iter().map(value).collect::<Vec<Value>>(),
+                )
+                .preview,
+            ),
+            _ => (),
+        }
+    }
+    if let Some(last) = rows.last().filter(|_| capped) {
+        sync.insert(
+            0,
+            format!(
+                "-- Stopped at the memory cap: rows after {} were not compared.",
+                last.key.shown()
+            ),
+        );
+    }
+    if !left_out.is_empty() {
+        sync.insert(
+            0,
+            format!(
+                "-- Left out, as their values could not be read: {}.",
+                left_out
+            ),
+        );
+    }
+
+    let other = match target {
+        Some(target) => format!("{} in {}", other, db::without_credentials(target)),
+        None => other.to_string(),
+    };
+    Ok(DataDiff {
+        table: table.into(),
+        other,
+        columns: names,
+        rows,
+        same,
+        sync,
+        capped,
+    })
+}
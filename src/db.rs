@@ -1,22 +1,135 @@
-use crate::args::{Args, Command};
-use color_eyre::Result;
+use crate::{
+    args::{Args, Command},
+    edit::{self, Locate, Statement},
+    plan::Plan,
+    schema,
+};
+use color_eyre::{Report, Result, eyre::eyre};
+use futures_util::{StreamExt, TryStreamExt, stream::BoxStream};
 use sqlx::{
-    MySql, Pool, Sqlite,
-    mysql::{MySqlConnectOptions, MySqlPoolOptions},
-    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Column, Either, MySql, Pool, QueryBuilder, Row, Sqlite, TypeInfo, Value, ValueRef,
+    mysql::{MySqlConnectOptions, MySqlPoolOptions, MySqlRow},
+    pool::PoolConnection,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow},
 };
-use std::time::Duration;
+use std::{mem, time::Duration};
 
+#[derive(Clone)]
 pub enum Database {
     SQLite(Pool<Sqlite>),
     MySQL(Pool<MySql>),
 }
 
+pub enum Connection {
+    SQLite(PoolConnection<Sqlite>),
+    MySQL(PoolConnection<MySql>),
+}
+
+/// What is needed to abort the statement currently running on a connection.
+#[derive(Clone, Copy)]
+pub enum Interrupt {
+    /// Raw `sqlite3*` handle, only valid while the owning connection is checked out.
+    SQLite(usize),
+    /// `CONNECTION_ID()` of the session to `KILL QUERY`.
+    MySQL(u64),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Dialect {
+    SQLite,
+    MySQL,
+}
+
+impl Dialect {
+    /// Quotes an identifier for use in generated SQL.
+    pub fn quote(&self, ident: &str) -> String {
+        match self {
+            Self::SQLite => format!("\"{}\"", ident.replace('"', "\"\"")),
+            Self::MySQL => format!("`{}`", ident.replace('`', "``")),
+        }
+    }
+
+    /// Quotes a string literal for use in generated SQL. MySQL reads backslashes as
+    /// escapes by default.
+    pub fn string(&self, text: &str) -> String {
+        match self {
+            Self::SQLite => format!("'{}'", text.replace('\'', "''")),
+            Self::MySQL => format!("'{}'", text.replace('\\', "\\\\").replace('\'', "''")),
+        }
+    }
+}
+
+/// What applying a set of generated statements did.
+#[derive(Default)]
+pub struct Applied {
+    pub affected: u64,
+    /// Id generated by the last `INSERT`.
+    pub last_id: Option<i64>,
+}
+
+/// The values of a row, `None` standing for NULL.
+pub type Values = Vec<Option<String>>;
+
+pub struct ResultSet {
+    pub columns: Vec<String>,
+    pub rows: Vec<Values>,
+    /// Rough size of the loaded values, checked against the memory cap.
+    pub bytes: usize,
+    pub fetched: Fetched,
+}
+
+/// How much of a result set has been loaded so far.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Fetched {
+    Complete,
+    /// More rows are waiting on an open cursor.
+    More,
+    /// The remaining rows were discarded when the cursor was closed.
+    Truncated,
+    /// Loading stopped at the memory cap.
+    Capped,
+}
+
+pub enum Item {
+    /// Values of a row, with the column names on the first one.
+    Row(Option<Vec<String>>, Values),
+    Affected(u64),
+}
+
+pub enum Outcome {
+    Rows(ResultSet),
+    Affected(u64),
+}
+
+impl ResultSet {
+    pub fn new(columns: Vec<String>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+            bytes: 0,
+            fetched: Fetched::Complete,
+        }
+    }
+
+    pub fn push(&mut self, values: Values) {
+        self.bytes += values
+            .iter()
+            .flatten()
+            .map(|value| value.len())
+            .sum::<usize>();
+        self.rows.push(values);
+    }
+}
+
 impl Database {
     pub async fn connect(args: &Args) -> Result<Self> {
         Ok(match &args.subcommand {
+            // Replaced by its connection on startup.
+            Command::Profile { name } => return Err(eyre!("Profile {} was not resolved.", name)),
             Command::Sqlite { filename } => {
-                let conn = SqliteConnectOptions::new().filename(filename);
+                let conn = SqliteConnectOptions::new()
+                    .filename(filename)
+                    .read_only(args.read_only);
                 Self::SQLite(
                     SqlitePoolOptions::new()
                         .min_connections(args.min_connections)
@@ -46,15 +159,610 @@ impl Database {
                     .port(*port)
                     .database(database);
 
+                let read_only = args.read_only;
+
                 Self::MySQL(
                     MySqlPoolOptions::new()
                         .min_connections(args.min_connections)
                         .max_connections(args.max_connections)
                         .acquire_timeout(Duration::from_millis(100))
+                        .after_connect(move |conn, _| {
+                            Box::pin(async move {
+                                if read_only {
+                                    sqlx::query("SET SESSION TRANSACTION READ ONLY")
+                                        .execute(&mut *conn)
+                                        .await?;
+                                }
+                                Ok(())
+                            })
+                        })
                         .connect_with(conn)
                         .await?,
                 )
             }
         })
     }
+
+    /// Opens another database on one connection: a `mysql://` or `mariadb://` URL,
+    /// or else a SQLite file, created when missing.
+    pub async fn open(target: &str) -> Result<Self> {
+        Ok(match target.split_once("://") {
+            Some(("mysql" | "mariadb", rest)) => Self::MySQL(
+                MySqlPoolOptions::new()
+                    .max_connections(1)
+                    .connect_with(format!("mysql://{}", rest).parse::<MySqlConnectOptions>()?)
+                    .await?,
+            ),
+            _ => Self::SQLite(
+                SqlitePoolOptions::new()
+                    .max_connections(1)
+                    .connect_with(
+                        SqliteConnectOptions::new()
+                            .filename(target)
+                            .create_if_missing(true),
+                    )
+                    .await?,
+            ),
+        })
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        match self {
+            Self::SQLite(_) => Dialect::SQLite,
+            Self::MySQL(_) => Dialect::MySQL,
+        }
+    }
+
+    pub async fn acquire(&self) -> Result<Connection> {
+        Ok(match self {
+            Self::SQLite(pool) => Connection::SQLite(pool.acquire().await?),
+            Self::MySQL(pool) => Connection::MySQL(pool.acquire().await?),
+        })
+    }
+
+    /// Aborts the statement running on the MySQL/MariaDB session `id` from another
+    /// pooled connection.
+    pub async fn kill_query(&self, id: u64) -> Result<()> {
+        if let Self::MySQL(pool) = self {
+            sqlx::raw_sql(&format!("KILL QUERY {}", id))
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A target of [`Database::open`] as shown, without the user and password of a URL.
+pub fn without_credentials(target: &str) -> String {
+    match (target.split_once("://"), target.rsplit_once('@')) {
+        (Some((scheme, _)), Some((_, host))) => format!("{}://{}", scheme, host),
+        _ => target.into(),
+    }
+}
+
+pub struct Snapshot {
+    pub tables: Vec<String>,
+    /// Triggers, routines and events, listed after the tables.
+    pub objects: Vec<schema::Object>,
+    pub selected: Option<usize>,
+    pub page: u16,
+    pub end: u16,
+    pub items: Option<(Vec<String>, Vec<Values>)>,
+    pub columns: Vec<schema::Column>,
+    /// Rowids of the page, for SQLite tables without a primary key.
+    pub rowids: Option<Vec<Option<String>>>,
+}
+
+impl Connection {
+    pub fn dialect(&self) -> Dialect {
+        match self {
+            Self::SQLite(_) => Dialect::SQLite,
+            Self::MySQL(_) => Dialect::MySQL,
+        }
+    }
+
+    pub async fn interrupt_handle(&mut self) -> Result<Interrupt> {
+        Ok(match self {
+            Self::SQLite(conn) => {
+                Interrupt::SQLite(conn.lock_handle().await?.as_raw_handle().as_ptr() as usize)
+            }
+            Self::MySQL(conn) => Interrupt::MySQL(
+                sqlx::query_scalar("SELECT CONNECTION_ID()")
+                    .fetch_one(&mut **conn)
+                    .await?,
+            ),
+        })
+    }
+
+    /// Streams the rows and affected counts of a single arbitrary statement.
+    pub fn stream<'c>(&'c mut self, sql: &'c str) -> BoxStream<'c, Result<Item>> {
+        let mut first = true;
+
+        match self {
+            Self::SQLite(conn) => sqlx::raw_sql(sql)
+                .fetch_many(&mut **conn)
+                .map(move |item| {
+                    Ok(match item? {
+                        Either::Left(result) => Item::Affected(result.rows_affected()),
+                        Either::Right(row) => Item::Row(
+                            mem::take(&mut first).then(|| column_names(&row)),
+                            sqlite_values(&row),
+                        ),
+                    })
+                })
+                .boxed(),
+            Self::MySQL(conn) => sqlx::raw_sql(sql)
+                .fetch_many(&mut **conn)
+                .map(move |item| {
+                    Ok(match item? {
+                        Either::Left(result) => Item::Affected(result.rows_affected()),
+                        Either::Right(row) => Item::Row(
+                            mem::take(&mut first).then(|| column_names(&row)),
+                            mysql_values(&row),
+                        ),
+                    })
+                })
+                .boxed(),
+        }
+    }
+
+    /// Runs a single arbitrary statement, collecting all of its rows.
+    pub async fn execute(&mut self, sql: &str) -> Result<Outcome> {
+        let mut stream = self.stream(sql);
+        let mut set: Option<ResultSet> = None;
+        let mut affected = 0;
+
+        while let Some(item) = stream.try_next().await? {
+            match item {
+                Item::Affected(n) => affected += n,
+                Item::Row(columns, values) => set
+                    .get_or_insert_with(|| ResultSet::new(columns.unwrap_or_default()))
+                    .push(values),
+            }
+        }
+
+        Ok(match set {
+            Some(set) => Outcome::Rows(set),
+            None => Outcome::Affected(affected),
+        })
+    }
+
+    /// Runs generated statements in order, all or none of them: in a transaction of
+    /// their own, or under a savepoint when `nested` in an open one.
+    pub async fn apply(&mut self, statements: &[Statement], nested: bool) -> Result<Applied> {
+        let (begin, commit, rollback) = if nested {
+            (
+                "SAVEPOINT termisql_changes",
+                "RELEASE SAVEPOINT termisql_changes",
+                "ROLLBACK TO SAVEPOINT termisql_changes; RELEASE SAVEPOINT termisql_changes",
+            )
+        } else {
+            ("BEGIN", "COMMIT", "ROLLBACK")
+        };
+
+        self.execute(begin).await?;
+
+        let mut applied = Applied::default();
+        for statement in statements {
+            match self.apply_one(statement).await {
+                Ok((affected, id)) => {
+                    applied.affected += affected;
+                    applied.last_id = id.or(applied.last_id);
+                }
+                Err(err) => {
+                    let _ = self.execute(rollback).await;
+                    return Err(err);
+                }
+            }
+        }
+
+        self.execute(commit).await?;
+
+        Ok(applied)
+    }
+
+    /// Runs one statement, returning the rows it affected and the id it generated.
+    async fn apply_one(&mut self, statement: &Statement) -> Result<(u64, Option<i64>)> {
+        Ok(match self {
+            Self::SQLite(conn) => {
+                let mut query = sqlx::query(&statement.sql);
+                for param in &statement.params {
+                    query = match param {
+                        edit::Value::Text(text) => query.bind(text),
+                        _ => query.bind(None::<String>),
+                    };
+                }
+                let result = query.execute(&mut **conn).await.map_err(referenced)?;
+                let is_insert = statement.sql.starts_with("INSERT");
+                (
+                    result.rows_affected(),
+                    is_insert.then(|| result.last_insert_rowid()),
+                )
+            }
+            Self::MySQL(conn) => {
+                let mut query = sqlx::query(&statement.sql);
+                for param in &statement.params {
+                    query = match param {
+                        edit::Value::Text(text) => query.bind(text),
+                        _ => query.bind(None::<String>),
+                    };
+                }
+                let result = query.execute(&mut **conn).await.map_err(referenced)?;
+                (
+                    result.rows_affected(),
+                    (result.last_insert_id() != 0).then(|| result.last_insert_id() as i64),
+                )
+            }
+        })
+    }
+
+    /// Counts the rows of `table` matching `filter`.
+    pub async fn count(&mut self, table: &str, filter: Option<&str>) -> Result<u64> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM {}{}",
+            self.dialect().quote(table),
+            filter.map_or(String::new(), |filter| format!(" WHERE {}", filter))
+        );
+
+        let count: i64 = match self {
+            Self::SQLite(conn) => sqlx::query_scalar(&sql).fetch_one(&mut **conn).await?,
+            Self::MySQL(conn) => sqlx::query_scalar(&sql).fetch_one(&mut **conn).await?,
+        };
+
+        Ok(count as u64)
+    }
+
+    /// Where an inserted row falls in the table's natural order, found by its key or
+    /// by the id the database generated for it.
+    pub async fn position(
+        &mut self,
+        table: &str,
+        locate: Locate,
+        id: Option<i64>,
+    ) -> Result<Option<u64>> {
+        // Rowid tables are stored in rowid order whatever their key.
+        let (columns, values) = match (locate, id) {
+            (_, Some(id)) if self.has_rowid(table).await? => {
+                (vec!["rowid".into()], vec![Some(id.to_string())])
+            }
+            (Locate::Key(key), _) => (key.columns, key.values),
+            (Locate::Generated(column), Some(id)) => (vec![column], vec![Some(id.to_string())]),
+            _ => return Ok(None),
+        };
+
+        let dialect = self.dialect();
+        let sql = format!(
+            "SELECT COUNT(*) FROM {} WHERE ({}) < ({})",
+            dialect.quote(table),
+            columns
+                .iter()
+                .map(|column| dialect.quote(column))
+                .collect::<Vec<String>>()
+                .join(", "),
+            vec!["?"; values.len()].join(", ")
+        );
+
+        let before: i64 = match self {
+            Self::SQLite(conn) => {
+                let mut query = sqlx::query_scalar(&sql);
+                for value in &values {
+                    query = query.bind(value);
+                }
+                query.fetch_one(&mut **conn).await?
+            }
+            Self::MySQL(conn) => {
+                let mut query = sqlx::query_scalar(&sql);
+                for value in &values {
+                    query = query.bind(value);
+                }
+                query.fetch_one(&mut **conn).await?
+            }
+        };
+
+        Ok(Some(before as u64))
+    }
+
+    /// Asks the database how it would run `sql`, without running it.
+    pub async fn explain(&mut self, sql: &str) -> Result<Plan> {
+        match self {
+            Self::SQLite(conn) => {
+                let explain = format!("EXPLAIN QUERY PLAN {}", sql);
+                let rows = sqlx::query(&explain)
+                    .map(|row: SqliteRow| (row.get(0), row.get(1), row.get(3)))
+                    .fetch_all(&mut **conn)
+                    .await?;
+
+                Ok(Plan::from_sqlite(sql.to_string(), rows))
+            }
+            Self::MySQL(conn) => {
+                let explain = format!("EXPLAIN FORMAT=JSON {}", sql);
+                let json: String = sqlx::query_scalar(&explain).fetch_one(&mut **conn).await?;
+
+                Plan::from_mysql_json(sql.to_string(), &json)
+            }
+        }
+    }
+
+    /// Lists the tables of the database, as the table list shows them.
+    pub async fn tables(&mut self) -> Result<Vec<String>> {
+        match self {
+            Self::SQLite(conn) => Ok(sqlx::query("SELECT name FROM sqlite_schema WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+                .map(|row: SqliteRow| row.get(0))
+                .fetch_all(&mut **conn)
+                .await?),
+            Self::MySQL(conn) => Ok(sqlx::query("SELECT table_name FROM INFORMATION_SCHEMA.TABLES WHERE table_schema = DATABASE()")
+                .map(|row: MySqlRow| row.get(0))
+                .fetch_all(&mut **conn)
+                .await?),
+        }
+    }
+
+    /// Loads the table listing and, if a table is selected, one page of its rows,
+    /// only those matching the `filter` condition when given.
+    pub async fn snapshot(
+        &mut self,
+        selected: Option<usize>,
+        page: u16,
+        size: u8,
+        filter: Option<&str>,
+    ) -> Result<Snapshot> {
+        let condition = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter));
+        let tables = self.tables().await?;
+        let objects = self.objects().await?;
+
+        match self {
+            Self::SQLite(conn) => {
+                let Some(selected) = clamp(selected, tables.len()) else {
+                    return Ok(Snapshot::empty(tables, objects));
+                };
+
+                let tablename = &tables[selected];
+
+                let rows_count: i64 = sqlx::query_scalar(&format!(
+                    "SELECT COUNT(*) FROM {}{}",
+                    Dialect::SQLite.quote(tablename),
+                    condition
+                ))
+                .fetch_one(&mut **conn)
+                .await?;
+
+                let (page, end) = paginate(rows_count, page, size);
+
+                let info = self.columns(tablename).await?;
+                let columns: Vec<String> = info.iter().map(|column| column.name.clone()).collect();
+
+                // Without a primary key, rows are told apart by their rowid.
+                let by_rowid =
+                    info.iter().all(|column| column.pk == 0) && self.has_rowid(tablename).await?;
+                let Self::SQLite(conn) = self else {
+                    unreachable!()
+                };
+
+                let mut qb = QueryBuilder::new(&format!(
+                    "SELECT {}{} FROM {}{} LIMIT ",
+                    columns
+                        .iter()
+                        .map(|column| Dialect::SQLite.quote(column))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    if by_rowid { ", rowid" } else { "" },
+                    Dialect::SQLite.quote(tablename),
+                    condition
+                ));
+
+                qb.push_bind(size)
+                    .push(" OFFSET ")
+                    .push_bind((page - 1) * size as u16);
+
+                let mut rows: Vec<Values> = qb
+                    .build()
+                    .map(|row: SqliteRow| sqlite_values(&row))
+                    .fetch_all(&mut **conn)
+                    .await?;
+
+                let rowids =
+                    by_rowid.then(|| rows.iter_mut().map(|row| row.pop().flatten()).collect());
+
+                Ok(Snapshot {
+                    tables,
+                    objects,
+                    selected: Some(selected),
+                    page,
+                    end,
+                    items: Some((columns, rows)),
+                    columns: info,
+                    rowids,
+                })
+            }
+            Self::MySQL(conn) => {
+                let Some(selected) = clamp(selected, tables.len()) else {
+                    return Ok(Snapshot::empty(tables, objects));
+                };
+
+                let tablename = &tables[selected];
+
+                let rows_count: i64 = sqlx::query_scalar(&format!(
+                    "SELECT COUNT(*) FROM {}{}",
+                    Dialect::MySQL.quote(tablename),
+                    condition
+                ))
+                .fetch_one(&mut **conn)
+                .await?;
+
+                let (page, end) = paginate(rows_count, page, size);
+
+                let info = self.columns(tablename).await?;
+                let Self::MySQL(conn) = self else {
+                    unreachable!()
+                };
+                let columns: Vec<String> = info.iter().map(|column| column.name.clone()).collect();
+
+                let mut qb = QueryBuilder::new(&format!(
+                    "SELECT {} FROM {}{} LIMIT ",
+                    columns
+                        .iter()
+                        .map(|column| Dialect::MySQL.quote(column))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    Dialect::MySQL.quote(tablename),
+                    condition
+                ));
+
+                qb.push_bind(size)
+                    .push(" OFFSET ")
+                    .push_bind((page - 1) * size as u16);
+
+                let rows: Vec<Values> = qb
+                    .build()
+                    .map(|row: MySqlRow| mysql_values(&row))
+                    .fetch_all(&mut **conn)
+                    .await?;
+
+                Ok(Snapshot {
+                    tables,
+                    objects,
+                    selected: Some(selected),
+                    page,
+                    end,
+                    items: Some((columns, rows)),
+                    columns: info,
+                    rowids: None,
+                })
+            }
+        }
+    }
+}
+
+impl Snapshot {
+    fn empty(tables: Vec<String>, objects: Vec<schema::Object>) -> Self {
+        Self {
+            tables,
+            objects,
+            selected: None,
+            page: 0,
+            end: 0,
+            items: None,
+            columns: Vec::new(),
+            rowids: None,
+        }
+    }
+}
+
+fn clamp(selected: Option<usize>, len: usize) -> Option<usize> {
+    match selected {
+        Some(_) if len == 0 => None,
+        Some(selected) => Some(selected.min(len - 1)),
+        None => None,
+    }
+}
+
+/// Explains foreign key violations, which are expected when deleting rows.
+fn referenced(err: sqlx::Error) -> Report {
+    match err.as_database_error() {
+        Some(db_err) if db_err.is_foreign_key_violation() => eyre!(
+            "Rows are still referenced by another table, or reference a missing one: {}",
+            db_err.message()
+        ),
+        _ => err.into(),
+    }
+}
+
+fn paginate(rows_count: i64, page: u16, size: u8) -> (u16, u16) {
+    let end = ((rows_count as f64 / size as f64).ceil() as u16).max(1);
+    let page = if page > end { end } else { page.max(1) };
+
+    (page, end)
+}
+
+pub fn column_names<R: Row>(row: &R) -> Vec<String> {
+    row.columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect()
+}
+
+/// How the grid shows a value, `None` standing for NULL.
+pub fn shown(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("NULL")
+}
+
+pub fn sqlite_values(row: &SqliteRow) -> Values {
+    let mut records: Values = Vec::new();
+
+    for idx in 0..row.len() {
+        let value = row.try_get_raw(idx).unwrap().to_owned();
+
+        let value_str = if value.is_null() {
+            None
+        } else {
+            Some(match value.type_info().name() {
+                "INTEGER" => value
+                    .try_decode::<i64>()
+                    .map_or("<err>".into(), |v| v.to_string()),
+                "REAL" => value
+                    .try_decode::<f64>()
+                    .map_or("<err>".into(), |v| v.to_string()),
+                "TEXT" => value.try_decode::<String>().unwrap_or("<err>".into()),
+                _ => "<unsupported>".into(),
+            })
+        };
+
+        records.push(value_str);
+    }
+
+    records
+}
+
+pub fn mysql_values(row: &MySqlRow) -> Values {
+    let mut records = Vec::new();
+
+    for idx in 0..row.len() {
+        let value = ValueRef::to_owned(&row.try_get_raw(idx).unwrap());
+
+        let value_str = if value.is_null() {
+            None
+        } else {
+            Some(match value.type_info().name() {
+                "INT" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "BIGINT" | "BOOLEAN" => value
+                    .try_decode::<i64>()
+                    .map(|v| v.to_string())
+                    .unwrap_or("<err>".into()),
+                "INT UNSIGNED" | "TINYINT UNSIGNED" | "SMALLINT UNSIGNED"
+                | "MEDIUMINT UNSIGNED" | "BIGINT UNSIGNED" | "YEAR" | "BIT" => value
+                    .try_decode_unchecked::<u64>()
+                    .map(|v| v.to_string())
+                    .unwrap_or("<err>".into()),
+                "FLOAT" | "DOUBLE" => value
+                    .try_decode::<f64>()
+                    .map(|v| v.to_string())
+                    .unwrap_or("<err>".into()),
+                "VARCHAR" | "CHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" => {
+                    value.try_decode::<String>().unwrap_or("<err>".into())
+                }
+                // Sent as text by both protocols; decimals keep every digit this way.
+                "DECIMAL" | "JSON" | "ENUM" | "SET" => value
+                    .try_decode_unchecked::<String>()
+                    .unwrap_or("<err>".into()),
+                "DATETIME" | "TIMESTAMP" => value
+                    .try_decode::<chrono::NaiveDateTime>()
+                    .map(|v| v.to_string())
+                    .unwrap_or("<err>".into()),
+                "DATE" => value
+                    .try_decode::<chrono::NaiveDate>()
+                    .map(|v| v.to_string())
+                    .unwrap_or("<err>".into()),
+                // Times stand for durations too, negative or over a day.
+                "TIME" => value
+                    .try_decode::<sqlx::mysql::types::MySqlTime>()
+                    .map(|v| v.to_string())
+                    .unwrap_or("<err>".into()),
+                _ => "<unsupported>".into(),
+            })
+        };
+
+        records.push(value_str);
+    }
+
+    records
 }
@@ -0,0 +1,1116 @@
+use crate::{
+    db::{self, Connection, Dialect, Outcome},
+    schema::{self, Column},
+    script,
+};
+use color_eyre::{Result, eyre::eyre};
+
+/// A column as declared in `CREATE TABLE` or `ADD COLUMN`.
+#[derive(Clone, Debug)]
+pub struct ColumnDef {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    /// Default value, as a SQL expression.
+    pub default: Option<String>,
+    pub pk: bool,
+    pub auto_increment: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum Operation {
+    CreateTable {
+        table: String,
+        columns: Vec<ColumnDef>,
+    },
+    RenameTable {
+        table: String,
+        to: String,
+    },
+    DropTable {
+        table: String,
+    },
+    Truncate {
+        table: String,
+    },
+    AddColumn {
+        table: String,
+        column: ColumnDef,
+    },
+    DropColumn {
+        table: String,
+        column: String,
+    },
+    RenameColumn {
+        table: String,
+        column: String,
+        to: String,
+    },
+    CreateIndex {
+        table: String,
+        name: String,
+        columns: Vec<String>,
+        unique: bool,
+    },
+    DropIndex {
+        table: String,
+        name: String,
+    },
+    /// Brings the columns of `table` to `columns`, adding, dropping and changing
+    /// them as needed.
+    Redefine {
+        table: String,
+        columns: Vec<ColumnDef>,
+    },
+}
+
+// Dropping the old table must not cascade to the rows referencing it.
+const FOREIGN_KEYS_OFF: &str = "PRAGMA foreign_keys = OFF";
+const FOREIGN_KEYS_ON: &str = "PRAGMA foreign_keys = ON";
+// Views and triggers naming the table would stop the renaming of its replacement.
+const LEGACY_ALTER_ON: &str = "PRAGMA legacy_alter_table = ON";
+const LEGACY_ALTER_OFF: &str = "PRAGMA legacy_alter_table = OFF";
+// Lists the rows whose references a rebuild broke.
+const FOREIGN_KEY_CHECK: &str = "PRAGMA foreign_key_check";
+
+/// Words opening a table constraint rather than a column in `CREATE TABLE`.
+const CONSTRAINTS: [&str; 5] = ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+
+/// Words of column constraints a [`ColumnDef`] cannot hold.
+const COLUMN_CONSTRAINTS: [&str; 7] = [
+    "CONSTRAINT",
+    "REFERENCES",
+    "CHECK",
+    "UNIQUE",
+    "COLLATE",
+    "GENERATED",
+    "CONFLICT",
+];
+
+const KEYWORDS: [&str; 8] = [
+    "NOT",
+    "NULL",
+    "PRIMARY",
+    "KEY",
+    "DEFAULT",
+    "AUTO_INCREMENT",
+    "AUTOINCREMENT",
+    "UNIQUE",
+];
+
+impl ColumnDef {
+    /// Reads `name type [NOT NULL] [PRIMARY KEY] [AUTO_INCREMENT] [DEFAULT value]`.
+    /// The value is a literal, quoted or not, or an expression in parentheses.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let words = words(text);
+        let is = |idx: usize, keyword: &str| {
+            words
+                .get(idx)
+                .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+        };
+
+        let Some(name) = words.first() else {
+            return Err("A column needs a name and a type.".into());
+        };
+
+        let mut idx = 1;
+        let mut data_type = Vec::new();
+        while idx < words.len() && !KEYWORDS.iter().any(|keyword| is(idx, keyword)) {
+            data_type.push(words[idx]);
+            idx += 1;
+        }
+        if data_type.is_empty() {
+            return Err(format!("{} needs a type.", name));
+        }
+
+        let mut column = Self {
+            name: name.to_string(),
+            data_type: data_type.join(" "),
+            nullable: true,
+            default: None,
+            pk: false,
+            auto_increment: false,
+        };
+
+        while idx < words.len() {
+            if is(idx, "NOT") && is(idx + 1, "NULL") {
+                column.nullable = false;
+                idx += 2;
+            } else if is(idx, "NULL") {
+                idx += 1;
+            } else if is(idx, "PRIMARY") && is(idx + 1, "KEY") {
+                column.pk = true;
+                column.nullable = false;
+                idx += 2;
+            } else if is(idx, "AUTO_INCREMENT") || is(idx, "AUTOINCREMENT") {
+                column.auto_increment = true;
+                idx += 1;
+            } else if is(idx, "DEFAULT") && idx + 1 < words.len() {
+                column.default = Some(words[idx + 1].to_string());
+                idx += 2;
+            } else {
+                return Err(format!(
+                    "Unexpected {} in the definition of {}.",
+                    words[idx], name
+                ));
+            }
+        }
+
+        Ok(column)
+    }
+
+    /// Whether both declare the same column, up to the case of the type. A key
+    /// column's nullability is left aside, as SQLite reports it loosely.
+    pub fn matches(&self, other: &ColumnDef) -> bool {
+        self.name == other.name
+            && self.data_type.eq_ignore_ascii_case(&other.data_type)
+            && (self.pk || self.nullable == other.nullable)
+            && self.default == other.default
+            && self.pk == other.pk
+            && self.auto_increment == other.auto_increment
+    }
+
+    pub fn from_column(column: &Column) -> Self {
+        Self {
+            name: column.name.clone(),
+            data_type: column.data_type.clone(),
+            nullable: column.nullable,
+            default: column.default.clone(),
+            pk: column.pk > 0,
+            auto_increment: column.auto_increment,
+        }
+    }
+
+    /// The definition, with the primary key inline when `inline_pk`.
+    fn sql(&self, dialect: Dialect, inline_pk: bool) -> String {
+        let mut sql = dialect.quote(&self.name);
+        // SQLite columns may go without a type.
+        if !self.data_type.is_empty() {
+            sql.push_str(&format!(" {}", self.data_type));
+        }
+
+        match dialect {
+            Dialect::SQLite => {
+                if inline_pk {
+                    sql.push_str(" PRIMARY KEY");
+                    if self.auto_increment {
+                        sql.push_str(" AUTOINCREMENT");
+                    }
+                } else if !self.nullable {
+                    sql.push_str(" NOT NULL");
+                }
+                if let Some(default) = &self.default {
+                    sql.push_str(&format!(" DEFAULT {}", default));
+                }
+            }
+            Dialect::MySQL => {
+                if !self.nullable {
+                    sql.push_str(" NOT NULL");
+                }
+                if let Some(default) = &self.default {
+                    sql.push_str(&format!(" DEFAULT {}", default));
+                }
+                if self.auto_increment {
+                    sql.push_str(" AUTO_INCREMENT");
+                }
+                if inline_pk {
+                    sql.push_str(" PRIMARY KEY");
+                }
+            }
+        }
+
+        sql
+    }
+}
+
+/// Splits `text` at whitespace outside quotes and parentheses, so that a quoted
+/// literal or a parenthesized expression stays one word.
+fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut depth = 0usize;
+    let mut quote = None;
+
+    for (idx, c) in text.char_indices() {
+        match quote {
+            // A doubled quote closes and reopens the literal.
+            Some(close) if c == close => quote = None,
+            Some(_) => (),
+            None if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    words.push(&text[start..idx]);
+                }
+                continue;
+            }
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '[' => quote = Some(']'),
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ => (),
+            },
+        }
+        start.get_or_insert(idx);
+    }
+    if let Some(start) = start {
+        words.push(&text[start..]);
+    }
+
+    words
+}
+
+/// Reads `[UNIQUE] column, ...` into the columns of an index and whether it is unique.
+pub fn parse_index(text: &str) -> (Vec<String>, bool) {
+    let text = text.trim();
+    let (text, unique) = match text.get(..7) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("UNIQUE ") => (&text[7..], true),
+        _ => (text, false),
+    };
+
+    (
+        text.split(',')
+            .map(|column| column.trim().to_string())
+            .filter(|column| !column.is_empty())
+            .collect(),
+        unique,
+    )
+}
+
+/// `CREATE TABLE`, with a single-column key inline, where SQLite's `AUTOINCREMENT`
+/// has to go.
+pub fn create_table(dialect: Dialect, table: &str, columns: &[ColumnDef]) -> String {
+    let key: Vec<&ColumnDef> = columns.iter().filter(|column| column.pk).collect();
+    let inline = key.len() == 1;
+
+    let mut definitions: Vec<String> = columns
+        .iter()
+        .map(|column| column.sql(dialect, inline && column.pk))
+        .collect();
+    if key.len() > 1 {
+        definitions.push(format!(
+            "PRIMARY KEY ({})",
+            key.iter()
+                .map(|column| dialect.quote(&column.name))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    format!(
+        "CREATE TABLE {} (\n  {}\n)",
+        dialect.quote(table),
+        definitions.join(",\n  ")
+    )
+}
+
+/// Splits the definitions of a `CREATE TABLE` statement at their top-level commas,
+/// comments left out, with the table options after the closing parenthesis.
+fn split_definitions(sql: &str) -> Option<(Vec<String>, String)> {
+    let mut definitions = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote = None;
+
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(close) = quote {
+            if c == close {
+                quote = None;
+            }
+            current.push(c);
+            continue;
+        }
+
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+                current.push(' ');
+                continue;
+            }
+            '(' if depth == 0 => {
+                depth = 1;
+                current.clear();
+                continue;
+            }
+            ')' if depth == 1 => {
+                definitions.push(current.trim().to_string());
+                let options: String = chars.collect();
+                let options = options.split("--").next().unwrap_or_default();
+                let options = options.split("/*").next().unwrap_or_default();
+                return Some((definitions, options.trim().to_string()));
+            }
+            ',' if depth == 1 => {
+                definitions.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '\'' | '"' | '`' => quote = Some(c),
+            '[' => quote = Some(']'),
+            _ => (),
+        }
+        current.push(c);
+    }
+
+    None
+}
+
+/// Whether `sql` names `column`, quoted or not. Names alike in other tables count too.
+fn mentions(sql: &str, column: &str) -> bool {
+    script::tokens(sql, Dialect::SQLite)
+        .iter()
+        .any(|(token, _)| token.eq_ignore_ascii_case(column))
+}
+
+/// The `CREATE TABLE` of `temporary` after `original`, the statement that created
+/// `table`, with its columns brought from `current` to `columns`.
+///
+/// Unchanged columns, table constraints and options are kept as written. Changes
+/// that would lose a constraint are refused.
+pub fn redefine(
+    table: &str,
+    original: &str,
+    temporary: &str,
+    current: &[ColumnDef],
+    columns: &[ColumnDef],
+) -> Result<String> {
+    let dialect = Dialect::SQLite;
+    let (definitions, options) = split_definitions(original)
+        .ok_or_else(|| eyre!("The definition of {} could not be read.", table))?;
+
+    let find = |columns: &[ColumnDef], name: &str| {
+        columns
+            .iter()
+            .position(|column| column.name.eq_ignore_ascii_case(name))
+    };
+    let key = |columns: &[ColumnDef]| -> Vec<String> {
+        columns
+            .iter()
+            .filter(|column| column.pk)
+            .map(|column| column.name.to_lowercase())
+            .collect()
+    };
+    let rekeyed = key(current) != key(columns);
+    let dropped: Vec<&str> = current
+        .iter()
+        .filter(|old| find(columns, &old.name).is_none())
+        .map(|old| old.name.as_str())
+        .collect();
+    let uses_dropped = |sql: &str| dropped.iter().find(|column| mentions(sql, column)).copied();
+
+    let mut written: Vec<(String, String)> = Vec::new();
+    let mut generated = Vec::new();
+    let mut constraints = Vec::new();
+    for definition in definitions {
+        let tokens = script::tokens(&definition, Dialect::SQLite);
+        let Some((first, quoted)) = tokens.first() else {
+            continue;
+        };
+        let is_constraint = !quoted
+            && CONSTRAINTS
+                .iter()
+                .any(|word| first.eq_ignore_ascii_case(word));
+
+        if is_constraint {
+            let is_key = tokens
+                .iter()
+                .take(3)
+                .any(|(token, quoted)| !quoted && token.eq_ignore_ascii_case("PRIMARY"));
+            if rekeyed && is_key {
+                continue;
+            }
+            if let Some(column) = uses_dropped(&definition) {
+                return Err(eyre!(
+                    "The constraint {} of {} uses {}, which would be dropped; change it by hand.",
+                    definition,
+                    table,
+                    column
+                ));
+            }
+            constraints.push(definition);
+        } else if find(current, first).is_some() {
+            written.push((first.clone(), definition));
+        } else {
+            // Generated columns are not among the columns SQLite reports.
+            if let Some(column) = uses_dropped(&definition) {
+                return Err(eyre!(
+                    "The generated column {} of {} uses {}, which would be dropped; change it by hand.",
+                    first,
+                    table,
+                    column
+                ));
+            }
+            generated.push(definition);
+        }
+    }
+
+    let key = key(columns);
+    let keyed = constraints.iter().any(|constraint| {
+        script::tokens(constraint, Dialect::SQLite)
+            .iter()
+            .take(3)
+            .any(|(token, quoted)| !quoted && token.eq_ignore_ascii_case("PRIMARY"))
+    });
+
+    let mut lines = Vec::new();
+    for column in columns {
+        let old = find(current, &column.name).map(|idx| &current[idx]);
+        let text = written
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&column.name))
+            .map(|(_, text)| text);
+
+        match (old, text) {
+            (Some(old), Some(text))
+                if old.matches(column) && !(rekeyed && (old.pk || column.pk)) =>
+            {
+                lines.push(text.clone())
+            }
+            (Some(_), Some(text))
+                if script::tokens(text, Dialect::SQLite)
+                    .iter()
+                    .any(|(token, quoted)| {
+                        !quoted
+                            && COLUMN_CONSTRAINTS
+                                .iter()
+                                .any(|word| token.eq_ignore_ascii_case(word))
+                    }) =>
+            {
+                return Err(eyre!(
+                    "Changing {} of {} would lose its constraints ({}); change it by hand.",
+                    column.name,
+                    table,
+                    text
+                ));
+            }
+            _ => lines.push(column.sql(dialect, column.pk && key.len() == 1 && !keyed)),
+        }
+    }
+    lines.extend(generated);
+    if rekeyed && key.len() > 1 {
+        lines.push(format!(
+            "PRIMARY KEY ({})",
+            columns
+                .iter()
+                .filter(|column| column.pk)
+                .map(|column| dialect.quote(&column.name))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+    lines.extend(constraints);
+
+    let mut sql = format!(
+        "CREATE TABLE {} (\n  {}\n)",
+        dialect.quote(temporary),
+        lines.join(",\n  ")
+    );
+    if !options.is_empty() {
+        sql.push_str(&format!(" {}", options));
+    }
+    Ok(sql)
+}
+
+impl Operation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::CreateTable { .. } => "Create table",
+            Self::RenameTable { .. } => "Rename table",
+            Self::DropTable { .. } => "Drop table",
+            Self::Truncate { .. } => "Truncate table",
+            Self::AddColumn { .. } => "Add column",
+            Self::DropColumn { .. } => "Drop column",
+            Self::RenameColumn { .. } => "Rename column",
+            Self::CreateIndex { .. } => "Create index",
+            Self::DropIndex { .. } => "Drop index",
+            Self::Redefine { .. } => "Redefine table",
+        }
+    }
+}
+
+impl Connection {
+    /// The statements carrying out `operation` in this database's dialect, to run
+    /// `nested` in an open transaction or on their own.
+    pub async fn ddl(&mut self, operation: &Operation, nested: bool) -> Result<Vec<String>> {
+        let dialect = self.dialect();
+        let q = |ident: &str| dialect.quote(ident);
+
+        // MySQL commits the open transaction before any schema change.
+        if nested && dialect == Dialect::MySQL {
+            return Err(eyre!(
+                "A schema change would commit the open transaction: commit or roll back first."
+            ));
+        }
+
+        Ok(match (dialect, operation) {
+            (_, Operation::CreateTable { table, columns }) => {
+                vec![create_table(dialect, table, columns)]
+            }
+            (Dialect::SQLite, Operation::RenameTable { table, to }) => {
+                vec![format!("ALTER TABLE {} RENAME TO {}", q(table), q(to))]
+            }
+            (Dialect::MySQL, Operation::RenameTable { table, to }) => {
+                vec![format!("RENAME TABLE {} TO {}", q(table), q(to))]
+            }
+            (_, Operation::DropTable { table }) => vec![format!("DROP TABLE {}", q(table))],
+            // SQLite has no TRUNCATE; an unfiltered DELETE is optimised the same way.
+            (Dialect::SQLite, Operation::Truncate { table }) => {
+                let mut statements = vec![format!("DELETE FROM {}", q(table))];
+                if self.has_sequence().await? {
+                    statements.push(format!(
+                        "DELETE FROM sqlite_sequence WHERE name = {}",
+                        dialect.string(table)
+                    ));
+                }
+                statements
+            }
+            (Dialect::MySQL, Operation::Truncate { table }) => {
+                vec![format!("TRUNCATE TABLE {}", q(table))]
+            }
+            // ALTER TABLE can neither add a key column nor a NOT NULL one without a
+            // default on SQLite.
+            (Dialect::SQLite, Operation::AddColumn { table, column })
+                if column.pk || (!column.nullable && column.default.is_none()) =>
+            {
+                let mut columns = self.definitions(table).await?;
+                let copied: Vec<String> =
+                    columns.iter().map(|column| column.name.clone()).collect();
+                columns.push(column.clone());
+
+                self.rebuild(table, &columns, &copied, nested).await?
+            }
+            (_, Operation::AddColumn { table, column }) => vec![format!(
+                "ALTER TABLE {} ADD COLUMN {}",
+                q(table),
+                column.sql(dialect, column.pk)
+            )],
+            // Nor can it drop a key or indexed column.
+            (Dialect::SQLite, Operation::DropColumn { table, column }) => {
+                let columns = self.columns(table).await?;
+                let indexed = self
+                    .indexes(table)
+                    .await?
+                    .iter()
+                    .any(|index| index.columns.contains(column));
+                let is_key = schema::primary_key(&columns).contains(column);
+
+                if is_key || indexed {
+                    let kept: Vec<ColumnDef> = self
+                        .definitions(table)
+                        .await?
+                        .into_iter()
+                        .filter(|kept| &kept.name != column)
+                        .collect();
+                    let copied: Vec<String> =
+                        kept.iter().map(|column| column.name.clone()).collect();
+
+                    self.rebuild(table, &kept, &copied, nested).await?
+                } else {
+                    vec![format!(
+                        "ALTER TABLE {} DROP COLUMN {}",
+                        q(table),
+                        q(column)
+                    )]
+                }
+            }
+            (Dialect::MySQL, Operation::DropColumn { table, column }) => {
+                vec![format!(
+                    "ALTER TABLE {} DROP COLUMN {}",
+                    q(table),
+                    q(column)
+                )]
+            }
+            (_, Operation::RenameColumn { table, column, to }) => vec![format!(
+                "ALTER TABLE {} RENAME COLUMN {} TO {}",
+                q(table),
+                q(column),
+                q(to)
+            )],
+            (
+                _,
+                Operation::CreateIndex {
+                    table,
+                    name,
+                    columns,
+                    unique,
+                },
+            ) => vec![format!(
+                "CREATE {}INDEX {} ON {} ({})",
+                if *unique { "UNIQUE " } else { "" },
+                q(name),
+                q(table),
+                columns
+                    .iter()
+                    .map(|column| q(column))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )],
+            (Dialect::SQLite, Operation::DropIndex { name, .. }) => {
+                vec![format!("DROP INDEX {}", q(name))]
+            }
+            (Dialect::MySQL, Operation::DropIndex { table, name }) => {
+                vec![format!("DROP INDEX {} ON {}", q(name), q(table))]
+            }
+            // Plain additions and removals go through ALTER TABLE, anything else
+            // through a rebuild.
+            (Dialect::SQLite, Operation::Redefine { table, columns }) => {
+                let current = self.definitions(table).await?;
+                let indexed: Vec<String> = self
+                    .indexes(table)
+                    .await?
+                    .into_iter()
+                    .flat_map(|index| index.columns)
+                    .collect();
+                let added: Vec<&ColumnDef> = columns
+                    .iter()
+                    .filter(|column| current.iter().all(|old| old.name != column.name))
+                    .collect();
+                let dropped: Vec<&ColumnDef> = current
+                    .iter()
+                    .filter(|old| columns.iter().all(|column| column.name != old.name))
+                    .collect();
+                let changed = columns.iter().any(|column| {
+                    current
+                        .iter()
+                        .any(|old| old.name == column.name && !old.matches(column))
+                });
+
+                if !changed
+                    && added
+                        .iter()
+                        .all(|column| !column.pk && (column.nullable || column.default.is_some()))
+                    && dropped
+                        .iter()
+                        .all(|old| !old.pk && !indexed.contains(&old.name))
+                {
+                    let mut statements: Vec<String> = added
+                        .iter()
+                        .map(|column| {
+                            format!(
+                                "ALTER TABLE {} ADD COLUMN {}",
+                                q(table),
+                                column.sql(dialect, false)
+                            )
+                        })
+                        .collect();
+                    statements.extend(dropped.iter().map(|old| {
+                        format!("ALTER TABLE {} DROP COLUMN {}", q(table), q(&old.name))
+                    }));
+                    statements
+                } else {
+                    let copied: Vec<String> = columns
+                        .iter()
+                        .filter(|column| current.iter().any(|old| old.name == column.name))
+                        .map(|column| column.name.clone())
+                        .collect();
+
+                    self.rebuild(table, columns, &copied, nested).await?
+                }
+            }
+            (Dialect::MySQL, Operation::Redefine { table, columns }) => {
+                let current = self.definitions(table).await?;
+                let key = |columns: &[ColumnDef]| -> Vec<String> {
+                    columns
+                        .iter()
+                        .filter(|column| column.pk)
+                        .map(|column| q(&column.name))
+                        .collect()
+                };
+
+                let mut clauses: Vec<String> = current
+                    .iter()
+                    .filter(|old| columns.iter().all(|column| column.name != old.name))
+                    .map(|old| format!("DROP COLUMN {}", q(&old.name)))
+                    .collect();
+                for (idx, column) in columns.iter().enumerate() {
+                    match current.iter().find(|old| old.name == column.name) {
+                        None => clauses.push(format!(
+                            "ADD COLUMN {} {}",
+                            column.sql(dialect, false),
+                            match idx {
+                                0 => "FIRST".into(),
+                                _ => format!("AFTER {}", q(&columns[idx - 1].name)),
+                            }
+                        )),
+                        Some(old) if !old.matches(column) => {
+                            clauses.push(format!("MODIFY COLUMN {}", column.sql(dialect, false)))
+                        }
+                        Some(_) => (),
+                    }
+                }
+                if key(&current) != key(columns) {
+                    if !key(&current).is_empty() {
+                        clauses.push("DROP PRIMARY KEY".into());
+                    }
+                    if !key(columns).is_empty() {
+                        clauses.push(format!("ADD PRIMARY KEY ({})", key(columns).join(", ")));
+                    }
+                }
+
+                if clauses.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![format!("ALTER TABLE {} {}", q(table), clauses.join(", "))]
+                }
+            }
+        })
+    }
+
+    /// Runs the statements of a schema change in order. When one fails, or a rebuild
+    /// leaves broken references, the transaction of the rebuild is rolled back and
+    /// foreign keys are enforced again.
+    pub async fn alter(&mut self, statements: &[String]) -> Result<()> {
+        for (idx, statement) in statements.iter().enumerate() {
+            let result = match self.execute(statement).await {
+                Ok(Outcome::Rows(set))
+                    if statement == FOREIGN_KEY_CHECK && !set.rows.is_empty() =>
+                {
+                    let row = &set.rows[0];
+                    Err(eyre!(
+                        "Foreign keys would be broken: row {} of {} references a missing row of {}. Nothing was changed.",
+                        db::shown(&row[1]),
+                        db::shown(&row[0]),
+                        db::shown(&row[2])
+                    ))
+                }
+                result => result.map(|_| ()),
+            };
+            if let Err(err) = result {
+                let ran = &statements[..idx];
+                if ran.iter().any(|statement| statement == "BEGIN") {
+                    let _ = self.execute("ROLLBACK").await;
+                }
+                if ran.iter().any(|statement| statement == FOREIGN_KEYS_OFF) {
+                    let _ = self.execute(FOREIGN_KEYS_ON).await;
+                }
+                if ran.iter().any(|statement| statement == LEGACY_ALTER_ON) {
+                    let _ = self.execute(LEGACY_ALTER_OFF).await;
+                }
+
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// SQLite's recipe for changes ALTER TABLE cannot make: build the table anew
+    /// with `columns`, copy the `copied` ones over, swap it in and restore the
+    /// indexes that still apply and the triggers.
+    ///
+    /// The original definition is rewritten, so its other constraints survive. The
+    /// old table is dropped with foreign keys off, which SQLite only allows outside
+    /// a transaction.
+    async fn rebuild(
+        &mut self,
+        table: &str,
+        columns: &[ColumnDef],
+        copied: &[String],
+        nested: bool,
+    ) -> Result<Vec<String>> {
+        if nested {
+            return Err(eyre!(
+                "Rebuilding {} needs foreign keys off, which SQLite cannot do in a transaction: commit or roll back first.",
+                table
+            ));
+        }
+
+        let dialect = Dialect::SQLite;
+        let temporary = format!("termisql_new_{}", table);
+        let (original, triggers) = self.creation(table).await?;
+        let current = self.definitions(table).await?;
+        let create = redefine(table, &original, &temporary, &current, columns)?;
+        if let Some((trigger, column)) = triggers.iter().find_map(|trigger| {
+            current
+                .iter()
+                .filter(|old| columns.iter().all(|column| column.name != old.name))
+                .find(|old| mentions(trigger, &old.name))
+                .map(|old| (trigger, &old.name))
+        }) {
+            return Err(eyre!(
+                "A trigger on {} uses {}, which would be dropped; change it by hand: {}",
+                table,
+                column,
+                trigger
+            ));
+        }
+        let copied = copied
+            .iter()
+            .map(|column| dialect.quote(column))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let mut statements = vec![FOREIGN_KEYS_OFF.to_string(), "BEGIN".into()];
+        statements.extend([
+            create,
+            format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {}",
+                dialect.quote(&temporary),
+                copied,
+                copied,
+                dialect.quote(table)
+            ),
+            format!("DROP TABLE {}", dialect.quote(table)),
+            LEGACY_ALTER_ON.into(),
+            format!(
+                "ALTER TABLE {} RENAME TO {}",
+                dialect.quote(&temporary),
+                dialect.quote(table)
+            ),
+            LEGACY_ALTER_OFF.into(),
+        ]);
+
+        // Indexes created explicitly are kept unless they cover a dropped column.
+        statements.extend(
+            self.indexes(table)
+                .await?
+                .into_iter()
+                .filter(|index| {
+                    index.columns.iter().all(|column| {
+                        column == schema::EXPRESSION
+                            || columns.iter().any(|kept| &kept.name == column)
+                    })
+                })
+                .filter_map(|index| index.sql),
+        );
+        // Dropping the table dropped its triggers too.
+        statements.extend(triggers);
+        statements.extend([
+            FOREIGN_KEY_CHECK.into(),
+            "COMMIT".into(),
+            FOREIGN_KEYS_ON.into(),
+        ]);
+
+        Ok(statements)
+    }
+
+    /// The statement that created a SQLite table, and those of its triggers.
+    pub async fn creation(&mut self, table: &str) -> Result<(String, Vec<String>)> {
+        let Self::SQLite(conn) = self else {
+            return Err(eyre!("Only SQLite tables are rebuilt."));
+        };
+
+        let sql = sqlx::query_scalar::<_, String>(
+            "SELECT sql FROM sqlite_schema WHERE type = 'table' AND name = ?",
+        )
+        .bind(table)
+        .fetch_one(&mut **conn)
+        .await?;
+        let triggers = sqlx::query_scalar::<_, String>(
+            "SELECT sql FROM sqlite_schema WHERE type = 'trigger' AND tbl_name = ?",
+        )
+        .bind(table)
+        .fetch_all(&mut **conn)
+        .await?;
+
+        Ok((sql, triggers))
+    }
+
+    /// The column definitions of a SQLite table, to declare it anew.
+    pub async fn definitions(&mut self, table: &str) -> Result<Vec<ColumnDef>> {
+        let columns = self.columns(table).await?;
+        let Self::SQLite(conn) = self else {
+            return Ok(columns.iter().map(ColumnDef::from_column).collect());
+        };
+
+        // Any INTEGER PRIMARY KEY is filled in, but only AUTOINCREMENT keeps ids unique
+        // for good.
+        let autoincrement = sqlx::query_scalar::<_, String>(
+            "SELECT sql FROM sqlite_schema WHERE type = 'table' AND name = ?",
+        )
+        .bind(table)
+        .fetch_one(&mut **conn)
+        .await?
+        .to_uppercase()
+        .contains("AUTOINCREMENT");
+
+        Ok(columns
+            .iter()
+            .map(|column| ColumnDef {
+                auto_increment: column.auto_increment && autoincrement,
+                ..ColumnDef::from_column(column)
+            })
+            .collect())
+    }
+
+    /// Whether `sqlite_sequence` exists, once an AUTOINCREMENT table was created.
+    async fn has_sequence(&mut self) -> Result<bool> {
+        let Self::SQLite(conn) = self else {
+            return Ok(false);
+        };
+
+        Ok(sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM sqlite_schema WHERE name = 'sqlite_sequence'",
+        )
+        .fetch_one(&mut **conn)
+        .await?
+            > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_a_full_definition() {
+        let column =
+            ColumnDef::parse("id BIGINT UNSIGNED not null PRIMARY KEY AUTO_INCREMENT DEFAULT 0")
+                .unwrap();
+        assert_eq!(column.name, "id");
+        assert_eq!(column.data_type, "BIGINT UNSIGNED");
+        assert!(!column.nullable);
+        assert!(column.pk);
+        assert!(column.auto_increment);
+        assert_eq!(column.default.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn parse_defaults_to_a_nullable_plain_column() {
+        let column = ColumnDef::parse("  note   VARCHAR(20)  NULL ").unwrap();
+        assert_eq!(column.name, "note");
+        assert_eq!(column.data_type, "VARCHAR(20)");
+        assert!(column.nullable);
+        assert!(!column.pk && !column.auto_increment);
+        assert_eq!(column.default, None);
+    }
+
+    #[test]
+    fn parse_takes_a_whole_default() {
+        let column =
+            ColumnDef::parse("made TEXT DEFAULT (datetime('now', 'localtime')) NOT NULL").unwrap();
+        assert_eq!(
+            column.default.as_deref(),
+            Some("(datetime('now', 'localtime'))")
+        );
+        assert!(!column.nullable);
+
+        let column = ColumnDef::parse("note DECIMAL(10, 2) DEFAULT 'it''s a b'").unwrap();
+        assert_eq!(column.data_type, "DECIMAL(10, 2)");
+        assert_eq!(column.default.as_deref(), Some("'it''s a b'"));
+    }
+
+    #[test]
+    fn parse_refuses_what_it_cannot_hold() {
+        assert!(ColumnDef::parse("").is_err());
+        assert!(ColumnDef::parse("id").is_err());
+        assert!(ColumnDef::parse("id NOT NULL").is_err());
+        assert!(ColumnDef::parse("id INTEGER UNIQUE").is_err());
+        assert!(ColumnDef::parse("id INTEGER PRIMARY").is_err());
+    }
+
+    fn columns(definitions: &[&str]) -> Vec<ColumnDef> {
+        definitions
+            .iter()
+            .map(|definition| ColumnDef::parse(definition).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn split_definitions_keeps_nested_and_quoted_commas() {
+        let sql = "CREATE TABLE \"a(b\" ( -- the key\n  id INTEGER PRIMARY KEY,\n  \"x,y\" TEXT DEFAULT 'p,q)',\n  c NUMERIC(10, 2) CHECK (c > 0), /* why; (not) */\n  UNIQUE (\"x,y\", c)\n) WITHOUT ROWID -- done";
+        let (definitions, options) = split_definitions(sql).unwrap();
+        assert_eq!(
+            definitions,
+            [
+                "id INTEGER PRIMARY KEY",
+                "\"x,y\" TEXT DEFAULT 'p,q)'",
+                "c NUMERIC(10, 2) CHECK (c > 0)",
+                "UNIQUE (\"x,y\", c)"
+            ]
+        );
+        assert_eq!(options, "WITHOUT ROWID");
+        assert!(split_definitions("CREATE TABLE t AS SELECT 1").is_none());
+    }
+
+    #[test]
+    fn redefine_keeps_what_is_left_as_written() {
+        let original = "CREATE TABLE t (\n  id INTEGER PRIMARY KEY, -- key\n  a TEXT NOT NULL UNIQUE,\n  b INTEGER,\n  CONSTRAINT named CHECK (a <> '')\n)";
+        let current = columns(&["id INTEGER PRIMARY KEY", "a TEXT NOT NULL", "b INTEGER"]);
+        let sql = redefine("t", original, "t_new", &current, &current[..2]).unwrap();
+        assert_eq!(
+            sql,
+            "CREATE TABLE \"t_new\" (\n  id INTEGER PRIMARY KEY,\n  a TEXT NOT NULL UNIQUE,\n  CONSTRAINT named CHECK (a <> '')\n)"
+        );
+    }
+
+    #[test]
+    fn redefine_writes_changed_columns_anew() {
+        let original = "CREATE TABLE t (id INTEGER PRIMARY KEY, b INTEGER)";
+        let current = columns(&["id INTEGER PRIMARY KEY", "b INTEGER"]);
+        let changed = columns(&["id INTEGER PRIMARY KEY", "b TEXT NOT NULL DEFAULT 'x y'"]);
+        assert_eq!(
+            redefine("t", original, "t_new", &current, &changed).unwrap(),
+            "CREATE TABLE \"t_new\" (\n  id INTEGER PRIMARY KEY,\n  \"b\" TEXT NOT NULL DEFAULT 'x y'\n)"
+        );
+    }
+
+    #[test]
+    fn redefine_handles_quoted_names() {
+        let original =
+            "CREATE TABLE \"my t\" (\"the id\" INTEGER PRIMARY KEY, [old name] TEXT, `x` INT)";
+        let current = columns(&["id INTEGER PRIMARY KEY", "n TEXT", "x INT"]);
+        let current: Vec<ColumnDef> = current
+            .into_iter()
+            .zip(["the id", "old name", "x"])
+            .map(|(column, name)| ColumnDef {
+                name: name.into(),
+                ..column
+            })
+            .collect();
+        assert_eq!(
+            redefine("my t", original, "t_new", &current, &current[..2]).unwrap(),
+            "CREATE TABLE \"t_new\" (\n  \"the id\" INTEGER PRIMARY KEY,\n  [old name] TEXT\n)"
+        );
+    }
+
+    #[test]
+    fn redefine_moves_a_changed_key_to_a_table_constraint() {
+        let original = "CREATE TABLE t (id INTEGER PRIMARY KEY, a TEXT, b INTEGER, UNIQUE (a))";
+        let current = columns(&["id INTEGER PRIMARY KEY", "a TEXT", "b INTEGER"]);
+        let rekeyed = columns(&["id INTEGER PRIMARY KEY", "a TEXT", "b INTEGER PRIMARY KEY"]);
+        assert_eq!(
+            redefine("t", original, "t_new", &current, &rekeyed).unwrap(),
+            "CREATE TABLE \"t_new\" (\n  \"id\" INTEGER NOT NULL,\n  a TEXT,\n  \"b\" INTEGER NOT NULL,\n  PRIMARY KEY (\"id\", \"b\"),\n  UNIQUE (a)\n)"
+        );
+    }
+
+    #[test]
+    fn redefine_keeps_generated_columns_and_options() {
+        let original = "CREATE TABLE t (id TEXT PRIMARY KEY, a TEXT, g TEXT GENERATED ALWAYS AS (upper(a)) STORED) WITHOUT ROWID";
+        let current = columns(&["id TEXT PRIMARY KEY", "a TEXT"]);
+        let mut added = current.clone();
+        added.extend(columns(&["c INTEGER DEFAULT 0"]));
+        assert_eq!(
+            redefine("t", original, "t_new", &current, &added).unwrap(),
+            "CREATE TABLE \"t_new\" (\n  id TEXT PRIMARY KEY,\n  a TEXT,\n  \"c\" INTEGER DEFAULT 0,\n  g TEXT GENERATED ALWAYS AS (upper(a)) STORED\n) WITHOUT ROWID"
+        );
+        assert!(redefine("t", original, "t_new", &current, &current[..1]).is_err());
+    }
+
+    #[test]
+    fn redefine_refuses_to_lose_constraints() {
+        let original = "CREATE TABLE t (id INTEGER PRIMARY KEY, a TEXT CHECK (a <> ''), b INTEGER REFERENCES p (id), CHECK (b > 0))";
+        let current = columns(&["id INTEGER PRIMARY KEY", "a TEXT", "b INTEGER"]);
+        // Changing a column with a constraint of its own.
+        let changed = columns(&["id INTEGER PRIMARY KEY", "a INTEGER", "b INTEGER"]);
+        assert!(redefine("t", original, "t_new", &current, &changed).is_err());
+        // Dropping a column a table constraint uses.
+        assert!(redefine("t", original, "t_new", &current, &current[..2]).is_err());
+        // Dropping one with its own constraint is fine.
+        let kept = [current[0].clone(), current[2].clone()];
+        assert!(redefine("t", original, "t_new", &current, &kept).is_ok());
+    }
+}
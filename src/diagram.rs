@@ -0,0 +1,338 @@
+use crate::{
+    db::Connection,
+    schema::{Column, ForeignKey},
+};
+use color_eyre::Result;
+use std::collections::HashMap;
+
+/// Empty cells between boxes side by side.
+const GAP_X: i32 = 6;
+/// Empty rows between ranks of boxes, where the lines run.
+const GAP_Y: i32 = 3;
+
+/// A table as the diagram shows it.
+pub struct Entity {
+    pub table: String,
+    pub columns: Vec<Column>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+/// Where the box of an entity sits, in cells from the top left of the diagram.
+pub struct Node {
+    pub entity: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Entity {
+    fn is_foreign(&self, column: &str) -> bool {
+        self.foreign_keys
+            .iter()
+            .any(|key| key.columns.iter().any(|name| name == column))
+    }
+
+    /// The key columns, marked, as listed in the box.
+    pub fn key_lines(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .filter_map(|column| {
+                let marks = match (column.pk > 0, self.is_foreign(&column.name)) {
+                    (true, true) => "PK FK",
+                    (true, false) => "PK",
+                    (false, true) => "FK",
+                    (false, false) => return None,
+                };
+                Some(format!("{:<5} {}", marks, column.name))
+            })
+            .collect()
+    }
+
+    fn size(&self) -> (i32, i32) {
+        let lines = self.key_lines();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .chain([self.table.chars().count()])
+            .max()
+            .unwrap_or_default() as i32
+            + 4;
+        let height = if lines.is_empty() {
+            3
+        } else {
+            lines.len() as i32 + 4
+        };
+        (width, height)
+    }
+}
+
+impl Node {
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+impl Connection {
+    /// Loads every table with its columns and foreign keys.
+    pub async fn entities(&mut self) -> Result<Vec<Entity>> {
+        let mut entities = Vec::new();
+        for table in self.tables().await? {
+            let columns = self.columns(&table).await?;
+            let foreign_keys = self.foreign_keys(&table).await?;
+            entities.push(Entity {
+                table,
+                columns,
+                foreign_keys,
+            });
+        }
+
+        entities.sort_by(|a, b| a.table.cmp(&b.table));
+        Ok(entities)
+    }
+}
+
+/// The entity a foreign key points at; table names match regardless of case
+/// when no exact match exists, as in SQLite.
+fn parent(entities: &[Entity], key: &ForeignKey) -> Option<usize> {
+    entities
+        .iter()
+        .position(|entity| entity.table == key.table)
+        .or_else(|| {
+            entities
+                .iter()
+                .position(|entity| entity.table.eq_ignore_ascii_case(&key.table))
+        })
+}
+
+/// Each foreign key between two different entities, as `(child, parent, key)`.
+pub fn edges(entities: &[Entity]) -> Vec<(usize, usize, &ForeignKey)> {
+    entities
+        .iter()
+        .enumerate()
+        .flat_map(|(child, entity)| {
+            entity.foreign_keys.iter().filter_map(move |key| {
+                parent(entities, key)
+                    .filter(|&parent| parent != child)
+                    .map(|parent| (child, parent, key))
+            })
+        })
+        .collect()
+}
+
+/// `center` with the entities it references and those referencing it.
+pub fn neighborhood(entities: &[Entity], center: usize) -> Vec<usize> {
+    let mut shown = vec![center];
+    for (child, parent, _) in edges(entities) {
+        for (near, far) in [(child, parent), (parent, child)] {
+            if near == center && !shown.contains(&far) {
+                shown.push(far);
+            }
+        }
+    }
+    shown.sort();
+    shown
+}
+
+/// Places the boxes of the `shown` entities in ranks: referenced tables above the
+/// tables referencing them, each rank ordered to sit under its parents.
+pub fn layout(entities: &[Entity], shown: &[usize]) -> Vec<Node> {
+    let edges: Vec<(usize, usize)> = edges(entities)
+        .into_iter()
+        .filter(|(child, parent, _)| shown.contains(child) && shown.contains(parent))
+        .map(|(child, parent, _)| (child, parent))
+        .collect();
+
+    // Longest path from a table referencing nothing; a cycle stops growing once
+    // its ranks would pass the number of tables.
+    let mut ranks: HashMap<usize, usize> = shown.iter().map(|&entity| (entity, 0)).collect();
+    for _ in 0..shown.len() {
+        let mut changed = false;
+        for &(child, parent) in &edges {
+            let rank = ranks[&parent] + 1;
+            if ranks[&child] < rank && rank < shown.len() {
+                ranks.insert(child, rank);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut y = 0;
+    for rank in 0..=ranks.values().copied().max().unwrap_or_default() {
+        let mut members: Vec<(Option<f64>, usize)> = shown
+            .iter()
+            .filter(|entity| ranks[entity] == rank)
+            .map(|&entity| {
+                let centers: Vec<f64> = edges
+                    .iter()
+                    .filter(|(child, _)| *child == entity)
+                    .filter_map(|(_, parent)| nodes.iter().find(|node| node.entity == *parent))
+                    .map(|node| node.center().0 as f64)
+                    .collect();
+                let barycenter = (!centers.is_empty())
+                    .then(|| centers.iter().sum::<f64>() / centers.len() as f64);
+                (barycenter, entity)
+            })
+            .collect();
+        // Tables under their parents first, the rest after them by name.
+        members.sort_by(|(a, a_entity), (b, b_entity)| match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => entities[*a_entity].table.cmp(&entities[*b_entity].table),
+        });
+
+        let mut x = 0;
+        let mut height = 0;
+        for (_, entity) in members {
+            let (width, h) = entities[entity].size();
+            nodes.push(Node {
+                entity,
+                x,
+                y,
+                width,
+                height: h,
+            });
+            x += width + GAP_X;
+            height = height.max(h);
+        }
+        y += height + GAP_Y;
+    }
+
+    nodes
+}
+
+/// A Mermaid `erDiagram` of the `shown` entities with all their columns.
+pub fn mermaid(entities: &[Entity], shown: &[usize]) -> String {
+    // Mermaid names take letters, digits, `_` and `-`, and no leading digit.
+    let word = |text: &str, allowed: &str| {
+        let word: String = text
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || allowed.contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        match word.chars().next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => word,
+            _ => format!("_{}", word),
+        }
+    };
+
+    let mut text = String::from("erDiagram\n");
+    for &idx in shown {
+        let entity = &entities[idx];
+        text.push_str(&format!("    {} {{\n", word(&entity.table, "_-")));
+        for column in &entity.columns {
+            let mut keys = Vec::new();
+            if column.pk > 0 {
+                keys.push("PK");
+            }
+            if entity.is_foreign(&column.name) {
+                keys.push("FK");
+            }
+            text.push_str(&format!(
+                "        {} {}{}\n",
+                word(
+                    if column.data_type.is_empty() {
+                        "ANY"
+                    } else {
+                        &column.data_type
+                    },
+                    "_-()"
+                ),
+                word(&column.name, "_-"),
+                if keys.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", keys.join(", "))
+                }
+            ));
+        }
+        text.push_str("    }\n");
+    }
+
+    for (child, parent, key) in edges(entities) {
+        if !shown.contains(&child) || !shown.contains(&parent) {
+            continue;
+        }
+        let optional = entities[child]
+            .columns
+            .iter()
+            .any(|column| column.nullable && key.columns.contains(&column.name));
+        text.push_str(&format!(
+            "    {} {}--o{{ {} : \"{}\"\n",
+            word(&entities[parent].table, "_-"),
+            if optional { "|o" } else { "||" },
+            word(&entities[child].table, "_-"),
+            key.columns.join(", ").replace('"', "'")
+        ));
+    }
+
+    text
+}
+
+/// A Graphviz DOT digraph of the `shown` entities, with an edge from each
+/// referencing table to the table it references.
+pub fn dot(entities: &[Entity], shown: &[usize]) -> String {
+    let id = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let html = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+
+    let mut text = String::from(
+        "digraph schema {\n    rankdir=BT;\n    node [shape=plaintext];\n    edge [arrowhead=normal];\n",
+    );
+    for &idx in shown {
+        let entity = &entities[idx];
+        let mut label = format!(
+            "<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\"><TR><TD BGCOLOR=\"lightgrey\"><B>{}</B></TD></TR>",
+            html(&entity.table)
+        );
+        for column in &entity.columns {
+            let mut keys = Vec::new();
+            if column.pk > 0 {
+                keys.push("PK");
+            }
+            if entity.is_foreign(&column.name) {
+                keys.push("FK");
+            }
+            label.push_str(&format!(
+                "<TR><TD ALIGN=\"LEFT\">{} {}{}</TD></TR>",
+                html(&column.name),
+                html(&column.data_type),
+                if keys.is_empty() {
+                    String::new()
+                } else {
+                    format!(" <I>{}</I>", keys.join(", "))
+                }
+            ));
+        }
+        label.push_str("</TABLE>");
+        text.push_str(&format!("    {} [label=<{}>];\n", id(&entity.table), label));
+    }
+
+    for (child, parent, key) in edges(entities) {
+        if shown.contains(&child) && shown.contains(&parent) {
+            text.push_str(&format!(
+                "    {} -> {} [label={}];\n",
+                id(&entities[child].table),
+                id(&entities[parent].table),
+                id(&key.columns.join(", "))
+            ));
+        }
+    }
+
+    text.push_str("}\n");
+    text
+}
@@ -0,0 +1,618 @@
+use crate::{
+    db::{self, Dialect},
+    schema::Column,
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+/// A value written by a generated statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Text(String),
+    /// The column's declared default.
+    Default,
+    /// A SQL expression, inlined as written.
+    Expression(String),
+}
+
+/// A generated statement with `?` placeholders, and the same SQL with the values
+/// inlined for showing before it runs.
+#[derive(Clone, Debug)]
+pub struct Statement {
+    pub sql: String,
+    pub preview: String,
+    pub params: Vec<Value>,
+}
+
+/// Identifies one row: the key columns, or `rowid`, with that row's values, `None`
+/// for NULL.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Key {
+    pub columns: Vec<String>,
+    pub values: Vec<Option<String>>,
+}
+
+/// How to find an inserted row again, to show the page holding it.
+#[derive(Clone, Debug)]
+pub enum Locate {
+    /// By the values given for its key.
+    Key(Key),
+    /// By the value the database generated for this key column.
+    Generated(String),
+    Unknown,
+}
+
+/// Keys deleted by one generated `DELETE`, keeping its placeholders well within
+/// what either database takes.
+pub const DELETE_BATCH: usize = 100;
+
+struct Builder {
+    dialect: Dialect,
+    sql: String,
+    preview: String,
+    params: Vec<Value>,
+}
+
+impl Value {
+    /// The value as the grid would show it.
+    pub fn shown(&self) -> String {
+        match self {
+            Self::Null => "NULL".into(),
+            Self::Text(text) => text.clone(),
+            Self::Default => "DEFAULT".into(),
+            Self::Expression(sql) => format!("={}", sql),
+        }
+    }
+
+    fn literal(&self, dialect: Dialect) -> String {
+        match self {
+            Self::Null => "NULL".into(),
+            Self::Text(text) => dialect.string(text),
+            Self::Default => "DEFAULT".into(),
+            Self::Expression(sql) => sql.clone(),
+        }
+    }
+}
+
+impl Key {
+    pub fn shown(&self) -> String {
+        self.columns
+            .iter()
+            .zip(&self.values)
+            .map(|(column, value)| format!("{} = {}", column, db::shown(value)))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl Builder {
+    fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            sql: String::new(),
+            preview: String::new(),
+            params: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, sql: &str) -> &mut Self {
+        self.sql.push_str(sql);
+        self.preview.push_str(sql);
+        self
+    }
+
+    fn bind(&mut self, value: Value) -> &mut Self {
+        self.preview.push_str(&value.literal(self.dialect));
+        match value {
+            Value::Default => self.sql.push_str("DEFAULT"),
+            Value::Expression(sql) => self.sql.push_str(&sql),
+            value => {
+                self.sql.push('?');
+                self.params.push(value);
+            }
+        }
+        self
+    }
+
+    /// Writes `value` into `column`; SQLite has no `DEFAULT` outside of `INSERT`, so
+    /// the declared default expression is inlined there instead.
+    fn assign(&mut self, dialect: Dialect, column: &Column, value: Value) -> &mut Self {
+        match (dialect, value) {
+            (Dialect::SQLite, Value::Default) => {
+                let default = column.default.clone().unwrap_or_else(|| "NULL".into());
+                self.push(&default)
+            }
+            (_, value) => self.bind(value),
+        }
+    }
+
+    fn matching(&mut self, dialect: Dialect, key: &Key) -> &mut Self {
+        self.push(" WHERE ").equals(dialect, key)
+    }
+
+    /// `column = value`, or `column IS NULL`, for each key column, joined with `AND`.
+    fn equals(&mut self, dialect: Dialect, key: &Key) -> &mut Self {
+        for (idx, (column, value)) in key.columns.iter().zip(&key.values).enumerate() {
+            if idx > 0 {
+                self.push(" AND ");
+            }
+            self.push(&dialect.quote(column));
+            match value {
+                Some(value) => self.push(" = ").bind(Value::Text(value.clone())),
+                None => self.push(" IS NULL"),
+            };
+        }
+        self
+    }
+
+    fn build(self) -> Statement {
+        Statement {
+            sql: self.sql,
+            preview: self.preview,
+            params: self.params,
+        }
+    }
+}
+
+/// The condition matching `key`, with its values inlined, for filtering rows by.
+pub fn condition(dialect: Dialect, key: &Key) -> String {
+    let mut builder = Builder::new(dialect);
+    builder.equals(dialect, key);
+    builder.preview
+}
+
+/// `UPDATE table SET column = value WHERE <key>`.
+pub fn update(
+    dialect: Dialect,
+    table: &str,
+    key: &Key,
+    column: &Column,
+    value: Value,
+) -> Statement {
+    let mut builder = Builder::new(dialect);
+    builder
+        .push(&format!(
+            "UPDATE {} SET {} = ",
+            dialect.quote(table),
+            dialect.quote(&column.name)
+        ))
+        .assign(dialect, column, value)
+        .matching(dialect, key);
+    builder.build()
+}
+
+/// `UPDATE table SET column = value` on every row matching `filter`, or all of them.
+pub fn bulk_update(
+    dialect: Dialect,
+    table: &str,
+    column: &Column,
+    value: Value,
+    filter: Option<&str>,
+) -> Statement {
+    let mut builder = Builder::new(dialect);
+    builder
+        .push(&format!(
+            "UPDATE {} SET {} = ",
+            dialect.quote(table),
+            dialect.quote(&column.name)
+        ))
+        .assign(dialect, column, value);
+    if let Some(filter) = filter {
+        builder.push(" WHERE ").push(filter);
+    }
+    builder.build()
+}
+
+/// `DELETE FROM table WHERE key IN (...)`, or the keys OR-ed together when there
+/// are several key columns. A NULL in a single-column key adds `OR key IS NULL`.
+pub fn delete(dialect: Dialect, table: &str, keys: &[Key]) -> Statement {
+    let mut builder = Builder::new(dialect);
+    builder.push(&format!("DELETE FROM {} WHERE ", dialect.quote(table)));
+
+    match keys {
+        [first, ..] if first.columns.len() == 1 => {
+            let column = dialect.quote(&first.columns[0]);
+            let given: Vec<&String> = keys
+                .iter()
+                .filter_map(|key| key.values[0].as_ref())
+                .collect();
+            if !given.is_empty() {
+                builder.push(&format!("{} IN (", column));
+                for (idx, value) in given.iter().enumerate() {
+                    if idx > 0 {
+                        builder.push(", ");
+                    }
+                    builder.bind(Value::Text((*value).clone()));
+                }
+                builder.push(")");
+            }
+            if given.len() < keys.len() {
+                if !given.is_empty() {
+                    builder.push(" OR ");
+                }
+                builder.push(&format!("{} IS NULL", column));
+            }
+        }
+        _ => {
+            for (idx, key) in keys.iter().enumerate() {
+                if idx > 0 {
+                    builder.push(" OR ");
+                }
+                builder.push("(").equals(dialect, key).push(")");
+            }
+        }
+    }
+
+    builder.build()
+}
+
+/// The `DELETE`s of any number of keys, [`DELETE_BATCH`] at a time.
+pub fn deletes(dialect: Dialect, table: &str, keys: &[Key]) -> Vec<Statement> {
+    keys.chunks(DELETE_BATCH)
+        .map(|keys| delete(dialect, table, keys))
+        .collect()
+}
+
+/// `INSERT INTO table (...) VALUES (...)`, leaving out columns set to their default.
+pub fn insert(dialect: Dialect, table: &str, columns: &[Column], values: &[Value]) -> Statement {
+    let given: Vec<(&Column, &Value)> = columns
+        .iter()
+        .zip(values)
+        .filter(|(_, value)| **value != Value::Default)
+        .collect();
+
+    let mut builder = Builder::new(dialect);
+    builder.push(&format!("INSERT INTO {}", dialect.quote(table)));
+
+    if given.is_empty() {
+        builder.push(match dialect {
+            Dialect::SQLite => " DEFAULT VALUES",
+            Dialect::MySQL => " () VALUES ()",
+        });
+        return builder.build();
+    }
+
+    builder.push(&format!(
+        " ({}) VALUES (",
+        given
+            .iter()
+            .map(|(column, _)| dialect.quote(&column.name))
+            .collect::<Vec<String>>()
+            .join(", ")
+    ));
+    for (idx, (_, value)) in given.into_iter().enumerate() {
+        if idx > 0 {
+            builder.push(", ");
+        }
+        builder.bind(value.clone());
+    }
+    builder.push(")");
+
+    builder.build()
+}
+
+/// Checks that `text` reads as a value of the column's declared type.
+pub fn validate(column: &Column, text: &str) -> Result<(), String> {
+    let data_type = column.data_type.to_lowercase();
+    let text = text.trim();
+    let fail = |expected: &str| Err(format!("{} expects {}", column.name, expected));
+
+    if data_type.contains("int") && !data_type.contains("point") {
+        if text.parse::<i128>().is_err() {
+            return fail("an integer");
+        }
+    } else if ["real", "floa", "doub", "dec", "numeric"]
+        .iter()
+        .any(|name| data_type.contains(name))
+    {
+        if text.parse::<f64>().is_err() {
+            return fail("a number");
+        }
+    } else if data_type.starts_with("bool") {
+        if !["0", "1", "true", "false"].contains(&text.to_lowercase().as_str()) {
+            return fail("true, false, 1 or 0");
+        }
+    } else if data_type.starts_with("datetime") || data_type.starts_with("timestamp") {
+        if [
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M:%S%.f",
+            "%Y-%m-%dT%H:%M:%S",
+        ]
+        .iter()
+        .all(|format| NaiveDateTime::parse_from_str(text, format).is_err())
+        {
+            return fail("a date and time like 2024-01-31 12:00:00");
+        }
+    } else if data_type.starts_with("date") {
+        if NaiveDate::parse_from_str(text, "%Y-%m-%d").is_err() {
+            return fail("a date like 2024-01-31");
+        }
+    } else if data_type.starts_with("time") {
+        if NaiveTime::parse_from_str(text, "%H:%M:%S").is_err() {
+            return fail("a time like 12:00:00");
+        }
+    } else if let Some(length) = declared_length(&data_type)
+        && (data_type.contains("char") || data_type.contains("binary"))
+        && text.chars().count() > length
+    {
+        return fail(&format!("at most {} characters", length));
+    }
+
+    Ok(())
+}
+
+/// The length in a type like `varchar(32)`.
+fn declared_length(data_type: &str) -> Option<usize> {
+    let (_, rest) = data_type.split_once('(')?;
+    let (length, _) = rest.split_once(')')?;
+    length.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str) -> Column {
+        Column {
+            name: name.into(),
+            data_type: data_type.into(),
+            nullable: true,
+            default: None,
+            pk: 0,
+            auto_increment: false,
+        }
+    }
+
+    fn key(columns: &[&str], values: &[Option<&str>]) -> Key {
+        Key {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            values: values.iter().map(|value| value.map(String::from)).collect(),
+        }
+    }
+
+    #[test]
+    fn update_binds_the_value_and_quotes_the_preview() {
+        let statement = update(
+            Dialect::SQLite,
+            "order \"items\"",
+            &key(&["id"], &[Some("7")]),
+            &column("note", "TEXT"),
+            Value::Text("it's".into()),
+        );
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "order ""items""" SET "note" = ? WHERE "id" = ?"#
+        );
+        assert_eq!(
+            statement.preview,
+            r#"UPDATE "order ""items""" SET "note" = 'it''s' WHERE "id" = '7'"#
+        );
+        assert_eq!(
+            statement.params,
+            [Value::Text("it's".into()), Value::Text("7".into())]
+        );
+
+        let statement = update(
+            Dialect::MySQL,
+            "orders",
+            &key(&["id"], &[Some("7")]),
+            &column("path", "TEXT"),
+            Value::Text(r"C:\temp".into()),
+        );
+        assert_eq!(
+            statement.preview,
+            r"UPDATE `orders` SET `path` = 'C:\\temp' WHERE `id` = '7'"
+        );
+    }
+
+    #[test]
+    fn update_matches_composite_and_null_keys() {
+        let statement = update(
+            Dialect::SQLite,
+            "lines",
+            &key(&["order", "sku"], &[Some("1"), None]),
+            &column("qty", "INTEGER"),
+            Value::Null,
+        );
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "lines" SET "qty" = ? WHERE "order" = ? AND "sku" IS NULL"#
+        );
+        assert_eq!(
+            statement.preview,
+            r#"UPDATE "lines" SET "qty" = NULL WHERE "order" = '1' AND "sku" IS NULL"#
+        );
+        assert_eq!(statement.params, [Value::Null, Value::Text("1".into())]);
+    }
+
+    #[test]
+    fn update_to_default_inlines_it_on_sqlite() {
+        let mut status = column("status", "TEXT");
+        status.default = Some("'new'".into());
+        let id = key(&["id"], &[Some("1")]);
+
+        let statement = update(Dialect::SQLite, "t", &id, &status, Value::Default);
+        assert_eq!(
+            statement.sql,
+            r#"UPDATE "t" SET "status" = 'new' WHERE "id" = ?"#
+        );
+        let statement = update(Dialect::MySQL, "t", &id, &status, Value::Default);
+        assert_eq!(
+            statement.sql,
+            "UPDATE `t` SET `status` = DEFAULT WHERE `id` = ?"
+        );
+    }
+
+    #[test]
+    fn delete_uses_in_for_one_key_column_and_or_for_several() {
+        let statement = delete(
+            Dialect::SQLite,
+            "t",
+            &[key(&["id"], &[Some("1")]), key(&["id"], &[Some("o'k")])],
+        );
+        assert_eq!(statement.sql, r#"DELETE FROM "t" WHERE "id" IN (?, ?)"#);
+        assert_eq!(
+            statement.preview,
+            r#"DELETE FROM "t" WHERE "id" IN ('1', 'o''k')"#
+        );
+
+        let statement = delete(
+            Dialect::MySQL,
+            "t",
+            &[
+                key(&["a", "b"], &[Some("1"), Some("2")]),
+                key(&["a", "b"], &[Some("3"), Some("4")]),
+            ],
+        );
+        assert_eq!(
+            statement.preview,
+            "DELETE FROM `t` WHERE (`a` = '1' AND `b` = '2') OR (`a` = '3' AND `b` = '4')"
+        );
+        assert_eq!(statement.params.len(), 4);
+    }
+
+    #[test]
+    fn delete_matches_null_key_values_with_is_null() {
+        let statement = delete(
+            Dialect::SQLite,
+            "t",
+            &[key(&["id"], &[Some("1")]), key(&["id"], &[None])],
+        );
+        assert_eq!(
+            statement.sql,
+            r#"DELETE FROM "t" WHERE "id" IN (?) OR "id" IS NULL"#
+        );
+        assert_eq!(statement.params, [Value::Text("1".into())]);
+
+        let statement = delete(Dialect::SQLite, "t", &[key(&["id"], &[None])]);
+        assert_eq!(statement.sql, r#"DELETE FROM "t" WHERE "id" IS NULL"#);
+
+        // The text "NULL" is a value like any other.
+        let statement = delete(Dialect::SQLite, "t", &[key(&["id"], &[Some("NULL")])]);
+        assert_eq!(
+            statement.preview,
+            r#"DELETE FROM "t" WHERE "id" IN ('NULL')"#
+        );
+    }
+
+    #[test]
+    fn delete_matches_composite_keys_with_nulls() {
+        let statement = delete(
+            Dialect::MySQL,
+            "lines",
+            &[
+                key(&["order", "sku"], &[Some("1"), None]),
+                key(&["order", "sku"], &[Some("1"), Some("a`b")]),
+            ],
+        );
+        assert_eq!(
+            statement.sql,
+            "DELETE FROM `lines` WHERE (`order` = ? AND `sku` IS NULL) OR (`order` = ? AND `sku` = ?)"
+        );
+        assert_eq!(
+            statement.params,
+            [
+                Value::Text("1".into()),
+                Value::Text("1".into()),
+                Value::Text("a`b".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn deletes_come_in_batches() {
+        let keys: Vec<Key> = (0..DELETE_BATCH * 2 + 1)
+            .map(|id| key(&["id"], &[Some(&id.to_string())]))
+            .collect();
+
+        let statements = deletes(Dialect::SQLite, "t", &keys);
+        let sizes: Vec<usize> = statements
+            .iter()
+            .map(|statement| statement.params.len())
+            .collect();
+        assert_eq!(sizes, [DELETE_BATCH, DELETE_BATCH, 1]);
+        assert_eq!(
+            statements[2].preview,
+            format!(r#"DELETE FROM "t" WHERE "id" IN ('{}')"#, DELETE_BATCH * 2)
+        );
+        assert!(deletes(Dialect::SQLite, "t", &[]).is_empty());
+    }
+
+    #[test]
+    fn insert_leaves_out_defaults() {
+        let columns = [
+            column("id", "INTEGER"),
+            column("name", "TEXT"),
+            column("note", "TEXT"),
+        ];
+
+        let statement = insert(
+            Dialect::SQLite,
+            "t",
+            &columns,
+            &[Value::Default, Value::Text("a".into()), Value::Null],
+        );
+        assert_eq!(
+            statement.sql,
+            r#"INSERT INTO "t" ("name", "note") VALUES (?, ?)"#
+        );
+        assert_eq!(
+            statement.preview,
+            r#"INSERT INTO "t" ("name", "note") VALUES ('a', NULL)"#
+        );
+
+        let defaults = [Value::Default, Value::Default, Value::Default];
+        assert_eq!(
+            insert(Dialect::SQLite, "t", &columns, &defaults).sql,
+            r#"INSERT INTO "t" DEFAULT VALUES"#
+        );
+        assert_eq!(
+            insert(Dialect::MySQL, "t", &columns, &defaults).sql,
+            "INSERT INTO `t` () VALUES ()"
+        );
+    }
+
+    #[test]
+    fn validate_refuses_text_a_cell_cannot_hold() {
+        assert!(validate(&column("qty", "INTEGER"), "12").is_ok());
+        assert_eq!(
+            validate(&column("qty", "INTEGER"), "twelve"),
+            Err("qty expects an integer".into())
+        );
+        assert!(validate(&column("note", "TEXT"), "anything").is_ok());
+    }
+
+    #[test]
+    fn validate_checks_each_type_class() {
+        let check = |data_type, text| validate(&column("c", data_type), text).is_ok();
+
+        assert!(check("BIGINT UNSIGNED", " 18446744073709551615 "));
+        assert!(!check("int(11)", "1.5"));
+        // Not an integer type, for all the "int" in its name.
+        assert!(check("POINT", "POINT(1 2)"));
+
+        assert!(check("DECIMAL(10,2)", "-12.50"));
+        assert!(check("double precision", "1e3"));
+        assert!(!check("REAL", "12,5"));
+
+        assert!(check("BOOLEAN", "TRUE"));
+        assert!(check("bool", "0"));
+        assert!(!check("BOOLEAN", "yes"));
+
+        assert!(check("DATETIME", "2024-01-31 12:00:00"));
+        assert!(check("TIMESTAMP", "2024-01-31T12:00:00"));
+        assert!(check("datetime(6)", "2024-01-31 12:00:00.123456"));
+        assert!(!check("DATETIME", "2024-01-31"));
+
+        assert!(check("DATE", "2024-02-29"));
+        assert!(!check("DATE", "2023-02-29"));
+
+        assert!(check("TIME", "23:59:59"));
+        assert!(!check("TIME", "24:00:00"));
+
+        assert!(check("VARCHAR(3)", "äöü"));
+        assert!(!check("varchar(3)", "abcd"));
+        assert!(!check("BINARY(2)", "abc"));
+        assert!(check("TEXT", "no length to exceed"));
+    }
+}
@@ -0,0 +1,32 @@
+use color_eyre::{Result, eyre::eyre};
+use std::{env, fs, io::Write, process::Command};
+
+/// Opens `text` in `$VISUAL`/`$EDITOR` (falling back to `vi`) and returns what was saved.
+///
+/// The terminal must already be restored, the editor takes it over until it exits.
+pub fn edit(text: &str) -> Result<String> {
+    // Created under a random name, readable by the user alone, and removed on drop.
+    let mut file = tempfile::Builder::new()
+        .prefix("termisql-")
+        .suffix(".sql")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+    let path = file.path();
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".into());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let status = Command::new(program).args(parts).arg(path).status();
+    // Read back by path, as editors often save by replacing the file.
+    let edited = fs::read_to_string(path);
+
+    if !status?.success() {
+        return Err(eyre!("{} exited with an error", program));
+    }
+
+    Ok(edited?)
+}
@@ -0,0 +1,432 @@
+use crate::{
+    compare::{self, Comparison},
+    copy::{Copy, Progress},
+    data_diff::{self, DataDiff},
+    db::{Connection, Database, Interrupt, Snapshot},
+    ddl::Operation,
+    diagram::Entity,
+    edit::{Key, Locate, Statement},
+    migrations::History,
+    plan::Plan,
+    schema::{Dependent, Detail, ForeignKey, Index},
+    script::{self, Fetch, StatementResult},
+};
+use color_eyre::{Result, eyre::eyre};
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use tokio::sync::{self, mpsc::UnboundedSender};
+
+/// Connection pinned by an explicit transaction; every job runs on it while set.
+pub type Pinned = Arc<sync::Mutex<Option<Connection>>>;
+
+pub enum Task {
+    Snapshot {
+        selected: Option<usize>,
+        page: u16,
+        size: u8,
+        filter: Option<String>,
+    },
+    Script {
+        statements: Vec<String>,
+        continue_on_error: bool,
+        fetch: Fetch,
+    },
+    Explain {
+        sql: String,
+    },
+    Structure {
+        table: String,
+    },
+    Keys {
+        table: String,
+    },
+    Definition {
+        table: String,
+    },
+    /// Finds the row the foreign key through `column` of a row points at, from
+    /// that row's values by column name.
+    Follow {
+        table: String,
+        column: String,
+        row: Vec<(String, Option<String>)>,
+    },
+    Diagram,
+    Migrations,
+    /// Compares the schema with that of another database, a SQLite file or a URL.
+    Compare {
+        target: String,
+    },
+    /// Compares the rows of `table` with those of `other`, in the `target` database
+    /// or in this one.
+    DataDiff {
+        table: String,
+        target: Option<String>,
+        other: String,
+        /// The pool, for reading the other table alongside this one.
+        db: Database,
+        max_bytes: usize,
+    },
+    /// Counts the rows referencing a row of `table`, given by its values.
+    Dependents {
+        table: String,
+        row: Vec<(String, Option<String>)>,
+    },
+    /// Counts the rows `statement` is about to change, for confirming it.
+    Preview {
+        table: String,
+        filter: Option<String>,
+        statement: Statement,
+    },
+    /// Staged changes, applied all or none; `locate` finds the last inserted row.
+    Apply {
+        statements: Vec<Statement>,
+        locate: Option<(String, Locate)>,
+    },
+    /// Works out the statements of a schema change, for reviewing them.
+    Ddl(Operation),
+    /// Runs the reviewed statements of a schema change.
+    Alter {
+        statements: Vec<String>,
+    },
+    /// Copies a table, publishing how far along it is through `progress`.
+    Copy {
+        copy: Copy,
+        progress: Progress,
+    },
+    Begin,
+    Commit,
+    Rollback,
+}
+
+pub enum Output {
+    Snapshot(Snapshot),
+    Script(Vec<StatementResult>),
+    Plan(Plan),
+    Structure(String, Vec<Detail>),
+    Keys(String, Vec<Index>, Vec<ForeignKey>),
+    Definition(String, Vec<String>),
+    /// The referenced table, and the key of the referenced row.
+    Follow(String, Key),
+    Dependents(String, Vec<Dependent>),
+    Diagram(Vec<Entity>),
+    Compared(Comparison),
+    Migrations(Vec<History>),
+    DataDiff(DataDiff),
+    Preview(u64, Statement),
+    /// Rows affected by applied changes, and where the last inserted row landed.
+    Applied {
+        affected: u64,
+        position: Option<(String, u64)>,
+    },
+    Ddl(Operation, Vec<String>),
+    /// How many statements a schema change ran.
+    Altered(usize),
+    /// The copy made and how many rows it holds.
+    Copied(Copy, u64),
+    /// Whether a transaction is open after a `Begin`, `Commit` or `Rollback`.
+    Transaction(bool),
+}
+
+/// A query running in the background, away from the event loop.
+pub struct Job {
+    pub id: u64,
+    pub label: &'static str,
+    pub kind: JobKind,
+    pub started: Instant,
+    pub canceller: Canceller,
+}
+
+/// What a job does, as far as handling its failure depends on it.
+#[derive(Clone, Copy, PartialEq)]
+pub enum JobKind {
+    /// Loading the table list and the selected table's page.
+    Snapshot,
+    /// Beginning, committing or rolling back, which cannot be cancelled: the app's
+    /// idea of the open transaction has to follow what the connection did.
+    Transaction,
+    Other,
+}
+
+/// Delivers a job's output to the event loop.
+///
+/// Only the first reply is delivered, which lets a job answer early and keep working,
+/// like a script serving its open cursor.
+pub struct Reply {
+    id: u64,
+    tx: Option<UnboundedSender<(u64, Result<Output>)>>,
+}
+
+/// Shared slot through which a running job publishes how to interrupt it.
+#[derive(Clone, Default)]
+pub struct Canceller(Arc<Mutex<Option<Interrupt>>>);
+
+/// Keeps a [`Canceller`] armed; the interrupt is withdrawn when this is dropped,
+/// which must happen before the connection it points to is released.
+pub struct Armed(Canceller);
+
+impl Task {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Snapshot { .. } => "Loading table",
+            Self::Preview { .. } => "Counting rows",
+            Self::Script { .. } => "Running script",
+            Self::Explain { .. } => "Explaining query",
+            Self::Structure { .. } => "Loading structure",
+            Self::Keys { .. } => "Loading keys",
+            Self::Definition { .. } => "Loading definition",
+            Self::Follow { .. } => "Following key",
+            Self::Dependents { .. } => "Counting references",
+            Self::Diagram => "Loading schema",
+            Self::Compare { .. } => "Comparing schemas",
+            Self::Migrations => "Loading migrations",
+            Self::DataDiff { .. } => "Comparing rows",
+            Self::Apply { .. } => "Applying changes",
+            Self::Ddl(_) => "Preparing DDL",
+            Self::Alter { .. } => "Altering schema",
+            Self::Copy { .. } => "Copying table",
+            Self::Begin => "Beginning transaction",
+            Self::Commit => "Committing",
+            Self::Rollback => "Rolling back",
+        }
+    }
+
+    pub fn kind(&self) -> JobKind {
+        match self {
+            Self::Snapshot { .. } => JobKind::Snapshot,
+            Self::Begin | Self::Commit | Self::Rollback => JobKind::Transaction,
+            _ => JobKind::Other,
+        }
+    }
+
+    /// Whether the task leaves the database as it is, so a read-only session may run it.
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Self::Apply { .. } | Self::Ddl(_) | Self::Alter { .. } => false,
+            // A copy into another database only reads this one.
+            Self::Copy { copy, .. } => copy.target.is_some(),
+            _ => true,
+        }
+    }
+
+    /// Runs on `conn`, which is `pinned` when an explicit transaction is open on it.
+    async fn run(self, conn: &mut Connection, pinned: bool, reply: &mut Reply) -> Result<Output> {
+        Ok(match self {
+            Self::Snapshot {
+                selected,
+                page,
+                size,
+                filter,
+            } => Output::Snapshot(
+                conn.snapshot(selected, page, size, filter.as_deref())
+                    .await?,
+            ),
+            Self::Preview {
+                table,
+                filter,
+                statement,
+            } => Output::Preview(conn.count(&table, filter.as_deref()).await?, statement),
+            Self::Script {
+                statements,
+                continue_on_error,
+                fetch,
+            } => {
+                Output::Script(script::run(conn, statements, continue_on_error, fetch, reply).await)
+            }
+            Self::Explain { sql } => Output::Plan(conn.explain(&sql).await?),
+            Self::Structure { table } => {
+                let details = conn.details(&table).await?;
+                Output::Structure(table, details)
+            }
+            Self::Keys { table } => {
+                let indexes = conn.indexes(&table).await?;
+                let foreign_keys = conn.foreign_keys(&table).await?;
+                Output::Keys(table, indexes, foreign_keys)
+            }
+            Self::Definition { table } => {
+                let statements = conn.create_statements(&table).await?;
+                Output::Definition(table, statements)
+            }
+            Self::Follow { table, column, row } => {
+                let foreign_key = conn
+                    .foreign_keys(&table)
+                    .await?
+                    .into_iter()
+                    .find(|key| key.columns.contains(&column))
+                    .ok_or_else(|| eyre!("{} is not part of a foreign key.", column))?;
+
+                let mut values = Vec::new();
+                for column in &foreign_key.columns {
+                    match row.iter().find(|(name, _)| name == column) {
+                        Some((_, Some(value))) => values.push(Some(value.clone())),
+                        _ => return Err(eyre!("{} is NULL; it references no row.", column)),
+                    }
+                }
+
+                Output::Follow(
+                    foreign_key.table,
+                    Key {
+                        columns: foreign_key.references,
+                        values,
+                    },
+                )
+            }
+            Self::Dependents { table, row } => {
+                let dependents = conn.dependents(&table, &row).await?;
+                Output::Dependents(table, dependents)
+            }
+            Self::Diagram => Output::Diagram(conn.entities().await?),
+            Self::Migrations => Output::Migrations(conn.migrations().await?),
+            Self::Compare { target } => Output::Compared(compare::compare(conn, &target).await?),
+            Self::DataDiff {
+                table,
+                target,
+                other,
+                db,
+                max_bytes,
+            } => Output::DataDiff(
+                data_diff::data_diff(conn, &db, &table, target.as_deref(), &other, max_bytes)
+                    .await?,
+            ),
+            Self::Apply { statements, locate } => {
+                let applied = conn.apply(&statements, pinned).await?;
+                let position = match locate {
+                    Some((table, locate)) => conn
+                        .position(&table, locate, applied.last_id)
+                        .await?
+                        .map(|position| (table, position)),
+                    None => None,
+                };
+
+                Output::Applied {
+                    affected: applied.affected,
+                    position,
+                }
+            }
+            Self::Ddl(operation) => {
+                let statements = conn.ddl(&operation, pinned).await?;
+                Output::Ddl(operation, statements)
+            }
+            Self::Alter { statements } => {
+                conn.alter(&statements).await?;
+                Output::Altered(statements.len())
+            }
+            Self::Copy { copy, progress } => {
+                let copied = conn.copy_table(&copy, pinned, &progress).await?;
+                Output::Copied(copy, copied)
+            }
+            Self::Begin => {
+                conn.execute("BEGIN").await?;
+                Output::Transaction(true)
+            }
+            Self::Commit => {
+                conn.execute("COMMIT").await?;
+                Output::Transaction(false)
+            }
+            Self::Rollback => {
+                conn.execute("ROLLBACK").await?;
+                Output::Transaction(false)
+            }
+        })
+    }
+}
+
+impl Job {
+    /// Runs `task` on the pinned connection, or on its own pooled one outside of a
+    /// transaction, and reports back through `tx`.
+    pub fn spawn(
+        db: Database,
+        pinned: Pinned,
+        id: u64,
+        task: Task,
+        tx: UnboundedSender<(u64, Result<Output>)>,
+    ) -> Self {
+        let label = task.label();
+        let kind = task.kind();
+        let canceller = Canceller::default();
+        let armer = canceller.clone();
+
+        tokio::spawn(async move {
+            let mut reply = Reply { id, tx: Some(tx) };
+
+            let result = async {
+                let mut pinned = pinned.lock().await;
+                let is_begin = matches!(task, Task::Begin);
+
+                let in_transaction = pinned.is_some();
+                let mut acquired = match pinned.as_mut() {
+                    Some(_) => None,
+                    None => Some(db.acquire().await?),
+                };
+                let conn = match acquired.as_mut() {
+                    Some(conn) => conn,
+                    None => pinned.as_mut().unwrap(),
+                };
+
+                let armed = armer.arm(conn.interrupt_handle().await?);
+                let output = task.run(conn, in_transaction, &mut reply).await?;
+                drop(armed);
+
+                match output {
+                    Output::Transaction(true) if is_begin => *pinned = acquired.take(),
+                    Output::Transaction(false) => *pinned = None,
+                    _ => (),
+                }
+
+                Ok(output)
+            }
+            .await;
+
+            reply.send(result);
+        });
+
+        Self {
+            id,
+            label,
+            kind,
+            started: Instant::now(),
+            canceller,
+        }
+    }
+}
+
+impl Reply {
+    pub fn send(&mut self, result: Result<Output>) {
+        if let Some(tx) = self.tx.take() {
+            let _ = tx.send((self.id, result));
+        }
+    }
+}
+
+impl Canceller {
+    pub fn arm(&self, interrupt: Interrupt) -> Armed {
+        *self.0.lock().unwrap() = Some(interrupt);
+        Armed(self.clone())
+    }
+
+    pub async fn cancel(&self, db: &Database) -> Result<()> {
+        let id = {
+            let slot = self.0.lock().unwrap();
+
+            match *slot {
+                Some(Interrupt::SQLite(handle)) => {
+                    // SAFETY: the slot is only armed while the job holds the connection,
+                    // and holding its lock keeps the job from releasing it meanwhile.
+                    unsafe { libsqlite3_sys::sqlite3_interrupt(handle as *mut _) };
+                    return Ok(());
+                }
+                Some(Interrupt::MySQL(id)) => id,
+                None => return Ok(()),
+            }
+        };
+
+        db.kill_query(id).await
+    }
+}
+
+impl Drop for Armed {
+    fn drop(&mut self) {
+        *self.0.0.lock().unwrap() = None;
+    }
+}
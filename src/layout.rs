@@ -5,10 +5,14 @@ pub struct UILayout {
     pub list_area: Rect,
     pub table_area: Rect,
     pub page_area: Rect,
+    pub status_area: Rect,
 }
 
 impl UILayout {
     pub fn new(area: Rect) -> Result<Self> {
+        let [area, status_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
         let [list_area, _, raw_table_area] = Layout::horizontal([
             Constraint::Percentage(30),
             Constraint::Length(1),
@@ -27,6 +31,7 @@ impl UILayout {
             list_area,
             table_area,
             page_area,
+            status_area,
         })
     }
 }
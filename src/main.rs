@@ -1,7 +1,21 @@
 mod app;
 mod args;
+mod changes;
+mod compare;
+mod copy;
+mod data_diff;
 mod db;
+mod ddl;
+mod diagram;
+mod edit;
+mod editor;
+mod job;
 mod layout;
+mod migrations;
+mod plan;
+mod profile;
+mod schema;
+mod script;
 mod widgets;
 
 use app::App;
@@ -18,7 +32,7 @@ async fn main() -> Result<()> {
 
     color_eyre::install()?;
 
-    let args = Args::parse();
+    let args = profile::resolve(Args::parse())?;
     let terminal = ratatui::init();
 
     App::build(args).await?.run(terminal).await
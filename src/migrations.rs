@@ -0,0 +1,180 @@
+use crate::db::{Connection, Outcome};
+use color_eyre::Result;
+use std::cmp::Ordering;
+
+/// A migration tool, known by the table it keeps its history in.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Tool {
+    Sqlx,
+    Diesel,
+    Refinery,
+    /// golang-migrate, Rails and others sharing the name.
+    SchemaMigrations,
+}
+
+/// One migration applied, or attempted, on this database.
+pub struct Migration {
+    pub version: String,
+    pub description: Option<String>,
+    pub applied: Option<String>,
+    pub checksum: Option<String>,
+    /// Whether it ran to the end, for the tools recording it.
+    pub success: Option<bool>,
+    /// Left half-applied, as golang-migrate marks it.
+    pub dirty: bool,
+}
+
+/// The migrations one tool recorded, oldest first.
+pub struct History {
+    pub tool: Tool,
+    pub table: String,
+    pub migrations: Vec<Migration>,
+}
+
+impl Tool {
+    pub const ALL: [Self; 4] = [
+        Self::Sqlx,
+        Self::Diesel,
+        Self::Refinery,
+        Self::SchemaMigrations,
+    ];
+
+    pub fn table(&self) -> &'static str {
+        match self {
+            Self::Sqlx => "_sqlx_migrations",
+            Self::Diesel => "__diesel_schema_migrations",
+            Self::Refinery => "refinery_schema_history",
+            Self::SchemaMigrations => "schema_migrations",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Sqlx => "sqlx",
+            Self::Diesel => "Diesel",
+            Self::Refinery => "refinery",
+            Self::SchemaMigrations => "golang-migrate/Rails",
+        }
+    }
+}
+
+impl Migration {
+    /// Whether it failed or was left dirty.
+    pub fn is_broken(&self) -> bool {
+        self.dirty || self.success == Some(false)
+    }
+}
+
+/// Orders versions numerically when both are numbers, as timestamps and counters are.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+impl Connection {
+    /// Reads the history of every migration tool with a table here.
+    pub async fn migrations(&mut self) -> Result<Vec<History>> {
+        let tables = self.tables().await?;
+
+        let mut histories = Vec::new();
+        for tool in Tool::ALL {
+            let Some(table) = tables
+                .iter()
+                .find(|table| table.eq_ignore_ascii_case(tool.table()))
+            else {
+                continue;
+            };
+            let columns: Vec<String> = self
+                .columns(table)
+                .await?
+                .into_iter()
+                .map(|column| column.name.to_lowercase())
+                .collect();
+            let has = |column: &str| columns.iter().any(|name| name == column);
+
+            // Version, description, applied time, checksum, success and dirtiness;
+            // CAST AS CHAR reads as text on both SQLite and MySQL.
+            let text = |column: &str| format!("CAST({} AS CHAR)", column);
+            let fields = match tool {
+                Tool::Sqlx => [
+                    text("version"),
+                    text("description"),
+                    text("installed_on"),
+                    "HEX(checksum)".into(),
+                    text("success"),
+                    "NULL".into(),
+                ],
+                Tool::Diesel => [
+                    text("version"),
+                    "NULL".into(),
+                    text("run_on"),
+                    "NULL".into(),
+                    "NULL".into(),
+                    "NULL".into(),
+                ],
+                Tool::Refinery => [
+                    text("version"),
+                    text("name"),
+                    text("applied_on"),
+                    text("checksum"),
+                    "NULL".into(),
+                    "NULL".into(),
+                ],
+                Tool::SchemaMigrations => [
+                    text("version"),
+                    "NULL".into(),
+                    if has("applied_at") {
+                        text("applied_at")
+                    } else {
+                        "NULL".into()
+                    },
+                    "NULL".into(),
+                    "NULL".into(),
+                    if has("dirty") {
+                        text("dirty")
+                    } else {
+                        "NULL".into()
+                    },
+                ],
+            };
+            let sql = format!(
+                "SELECT {} FROM {}",
+                fields.join(", "),
+                self.dialect().quote(table)
+            );
+
+            let rows = match self.execute(&sql).await? {
+                Outcome::Rows(set) => set.rows,
+                Outcome::Affected(_) => Vec::new(),
+            };
+            let flag = |value: &Option<String>| match value.as_deref()?.to_lowercase().as_str() {
+                "1" | "true" | "t" => Some(true),
+                "0" | "false" | "f" => Some(false),
+                _ => None,
+            };
+
+            let mut migrations: Vec<Migration> = rows
+                .iter()
+                .map(|row| Migration {
+                    version: row[0].clone().unwrap_or_default(),
+                    description: row[1].clone(),
+                    applied: row[2].clone(),
+                    checksum: row[3].clone(),
+                    success: flag(&row[4]),
+                    dirty: flag(&row[5]) == Some(true),
+                })
+                .collect();
+            migrations.sort_by(|a, b| compare_versions(&a.version, &b.version));
+
+            histories.push(History {
+                tool,
+                table: table.clone(),
+                migrations,
+            });
+        }
+
+        Ok(histories)
+    }
+}
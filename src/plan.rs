@@ -0,0 +1,129 @@
+use color_eyre::Result;
+use serde_json::Value;
+
+pub struct PlanNode {
+    pub depth: usize,
+    pub detail: String,
+    pub full_scan: bool,
+}
+
+#[derive(Default)]
+pub struct Plan {
+    pub sql: String,
+    pub cost: Option<f64>,
+    pub nodes: Vec<PlanNode>,
+}
+
+impl Plan {
+    /// Builds the tree from `EXPLAIN QUERY PLAN` rows of `(id, parent, detail)`.
+    pub fn from_sqlite(sql: String, rows: Vec<(i64, i64, String)>) -> Self {
+        let mut depths: Vec<(i64, usize)> = Vec::new();
+        let mut nodes = Vec::with_capacity(rows.len());
+
+        for (id, parent, detail) in rows {
+            let depth = depths
+                .iter()
+                .find(|(node, _)| *node == parent)
+                .map_or(0, |(_, depth)| depth + 1);
+            depths.push((id, depth));
+
+            nodes.push(PlanNode {
+                depth,
+                full_scan: detail.starts_with("SCAN")
+                    && !detail.contains("USING")
+                    && detail != "SCAN CONSTANT ROW",
+                detail,
+            });
+        }
+
+        Self {
+            sql,
+            cost: None,
+            nodes,
+        }
+    }
+
+    /// Flattens the `table` entries of an `EXPLAIN FORMAT=JSON` document.
+    ///
+    /// Works with both the MySQL layout (`cost_info`, `rows_examined_per_scan`) and
+    /// the MariaDB one (`rows`, `r_rows`).
+    pub fn from_mysql_json(sql: String, json: &str) -> Result<Self> {
+        let root: Value = serde_json::from_str(json)?;
+        let mut nodes = Vec::new();
+
+        let cost = root
+            .pointer("/query_block/cost_info/query_cost")
+            .and_then(number);
+
+        walk(&root, 0, &mut nodes);
+
+        Ok(Self { sql, cost, nodes })
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn walk(value: &Value, depth: usize, nodes: &mut Vec<PlanNode>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if key == "table"
+                    && let Value::Object(table) = value
+                {
+                    nodes.push(table_node(table, depth));
+                    walk(value, depth + 1, nodes);
+                } else if key.ends_with("_operation") || key == "query_block" {
+                    nodes.push(PlanNode {
+                        depth,
+                        detail: key.replace('_', " "),
+                        full_scan: false,
+                    });
+                    walk(value, depth + 1, nodes);
+                } else {
+                    walk(value, depth, nodes);
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| walk(value, depth, nodes)),
+        _ => (),
+    }
+}
+
+fn table_node(table: &serde_json::Map<String, Value>, depth: usize) -> PlanNode {
+    let text = |key: &str| table.get(key).and_then(Value::as_str);
+
+    let access = text("access_type").unwrap_or("?");
+    let mut detail = format!("{} — access: {}", text("table_name").unwrap_or("?"), access);
+
+    if let Some(key) = text("key") {
+        detail.push_str(&format!(", key: {}", key));
+    }
+
+    if let Some(rows) = table
+        .get("rows_examined_per_scan")
+        .or_else(|| table.get("rows"))
+        .and_then(number)
+    {
+        detail.push_str(&format!(", rows: {}", rows));
+    }
+
+    if let Some(cost) = table
+        .get("cost_info")
+        .and_then(|cost| cost.get("prefix_cost"))
+        .and_then(number)
+    {
+        detail.push_str(&format!(", cost: {}", cost));
+    }
+
+    PlanNode {
+        depth,
+        detail,
+        full_scan: access == "ALL",
+    }
+}
@@ -0,0 +1,857 @@
+use crate::{
+    db::{Connection, Dialect},
+    edit::{self, Key},
+};
+use color_eyre::Result;
+use futures_util::TryStreamExt;
+use libsqlite3_sys::{SQLITE_OK, sqlite3, sqlite3_table_column_metadata};
+use sqlx::{Row, mysql::MySqlRow, sqlite::SqliteRow};
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    ptr,
+};
+
+/// Declared metadata of a table column.
+#[derive(Clone, Debug)]
+pub struct Column {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    /// Position in the primary key, starting at 1, or 0 when not part of it.
+    pub pk: u32,
+    pub auto_increment: bool,
+}
+
+/// A column as the structure view shows it, with what editing has no use for.
+#[derive(Clone, Debug)]
+pub struct Detail {
+    pub column: Column,
+    pub collation: Option<String>,
+    pub comment: Option<String>,
+    /// Whether the column is generated or hidden, and how.
+    pub extra: Option<String>,
+}
+
+/// An index of a table.
+#[derive(Clone, Debug)]
+pub struct Index {
+    pub name: String,
+    /// Indexed columns in order, with [`EXPRESSION`] for an indexed expression.
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub primary: bool,
+    /// Condition of a partial index.
+    pub partial: Option<String>,
+    /// Statement of an index created explicitly on SQLite.
+    pub sql: Option<String>,
+}
+
+/// A foreign key from columns of a table to those of the table it references.
+#[derive(Clone, Debug)]
+pub struct ForeignKey {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub table: String,
+    pub references: Vec<String>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObjectKind {
+    Trigger,
+    Procedure,
+    Function,
+    Event,
+}
+
+/// Code stored in the database next to the tables: a trigger, routine or event.
+#[derive(Clone, Debug)]
+pub struct Object {
+    pub kind: ObjectKind,
+    pub name: String,
+    /// Table a trigger fires on.
+    pub table: Option<String>,
+    /// When it runs: a trigger's timing and event, or an event's schedule. For a
+    /// function, the type it returns.
+    pub timing: String,
+    pub definer: Option<String>,
+    pub definition: String,
+}
+
+impl ObjectKind {
+    pub const ALL: [Self; 4] = [Self::Trigger, Self::Procedure, Self::Function, Self::Event];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Trigger => "Trigger",
+            Self::Procedure => "Procedure",
+            Self::Function => "Function",
+            Self::Event => "Event",
+        }
+    }
+}
+
+/// A foreign key pointing at one row, and how many rows of its table do.
+pub struct Dependent {
+    pub table: String,
+    pub foreign_key: ForeignKey,
+    /// The referencing columns with the row's values, or `None` when one is NULL.
+    pub key: Option<Key>,
+    pub count: u64,
+}
+
+/// Approximate size of a table, as the table list shows it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TableStats {
+    pub rows: Option<u64>,
+    /// Bytes taken by the rows and the indexes.
+    pub bytes: Option<u64>,
+}
+
+/// Stands for an indexed expression among the columns of an index.
+pub const EXPRESSION: &str = "<expression>";
+
+impl Connection {
+    /// Lists the columns of `table` in declaration order.
+    pub async fn columns(&mut self, table: &str) -> Result<Vec<Column>> {
+        match self {
+            Self::SQLite(conn) => {
+                let mut columns = sqlx::query(
+                    "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?)",
+                )
+                .bind(table)
+                .map(|row: SqliteRow| Column {
+                    name: row.get(0),
+                    data_type: row.get(1),
+                    nullable: !row.get::<bool, _>(2),
+                    default: row.get(3),
+                    pk: row.get::<i64, _>(4) as u32,
+                    auto_increment: false,
+                })
+                .fetch_all(&mut **conn)
+                .await?;
+
+                mark_rowid_alias(&mut columns);
+
+                Ok(columns)
+            }
+            Self::MySQL(conn) => Ok(sqlx::query(
+                "SELECT CAST(c.COLUMN_NAME AS CHAR), CAST(c.COLUMN_TYPE AS CHAR),
+                    c.IS_NULLABLE = 'YES', CAST(c.COLUMN_DEFAULT AS CHAR),
+                    CAST(COALESCE(k.ORDINAL_POSITION, 0) AS SIGNED),
+                    c.EXTRA LIKE '%auto_increment%'
+                FROM INFORMATION_SCHEMA.COLUMNS c
+                LEFT JOIN INFORMATION_SCHEMA.KEY_COLUMN_USAGE k
+                    ON k.TABLE_SCHEMA = c.TABLE_SCHEMA AND k.TABLE_NAME = c.TABLE_NAME
+                    AND k.COLUMN_NAME = c.COLUMN_NAME AND k.CONSTRAINT_NAME = 'PRIMARY'
+                WHERE c.TABLE_SCHEMA = DATABASE() AND c.TABLE_NAME = ?
+                ORDER BY c.ORDINAL_POSITION",
+            )
+            .bind(table)
+            .map(|row: MySqlRow| Column {
+                name: row.get(0),
+                data_type: row.get(1),
+                nullable: row.get::<i64, _>(2) != 0,
+                default: row.get(3),
+                pk: row.get::<i64, _>(4) as u32,
+                auto_increment: row.get::<i64, _>(5) != 0,
+            })
+            .fetch_all(&mut **conn)
+            .await?),
+        }
+    }
+
+    /// Describes every column of `table`, generated and hidden ones included.
+    pub async fn details(&mut self, table: &str) -> Result<Vec<Detail>> {
+        match self {
+            Self::SQLite(conn) => {
+                let rows = sqlx::query(
+                    "SELECT name, type, \"notnull\", dflt_value, pk, hidden
+                    FROM pragma_table_xinfo(?)",
+                )
+                .bind(table)
+                .map(|row: SqliteRow| {
+                    (
+                        Column {
+                            name: row.get(0),
+                            data_type: row.get(1),
+                            nullable: !row.get::<bool, _>(2),
+                            default: row.get(3),
+                            pk: row.get::<i64, _>(4) as u32,
+                            auto_increment: false,
+                        },
+                        row.get::<i64, _>(5),
+                    )
+                })
+                .fetch_all(&mut **conn)
+                .await?;
+
+                let (mut columns, hidden): (Vec<Column>, Vec<i64>) = rows.into_iter().unzip();
+                mark_rowid_alias(&mut columns);
+
+                let mut handle = conn.lock_handle().await?;
+                let collations: Vec<Option<String>> = columns
+                    .iter()
+                    .map(|column| collation(handle.as_raw_handle().as_ptr(), table, &column.name))
+                    .collect();
+                drop(handle);
+
+                Ok(columns
+                    .into_iter()
+                    .zip(hidden)
+                    .zip(collations)
+                    .map(|((column, hidden), collation)| Detail {
+                        column,
+                        collation,
+                        comment: None,
+                        extra: match hidden {
+                            1 => Some("hidden".into()),
+                            2 => Some("generated (virtual)".into()),
+                            3 => Some("generated (stored)".into()),
+                            _ => None,
+                        },
+                    })
+                    .collect())
+            }
+            Self::MySQL(conn) => Ok(sqlx::query(
+                "SELECT CAST(c.COLUMN_NAME AS CHAR), CAST(c.COLUMN_TYPE AS CHAR),
+                    c.IS_NULLABLE = 'YES', CAST(c.COLUMN_DEFAULT AS CHAR),
+                    CAST(COALESCE(k.ORDINAL_POSITION, 0) AS SIGNED),
+                    c.EXTRA LIKE '%auto_increment%', CAST(c.COLLATION_NAME AS CHAR),
+                    CAST(c.COLUMN_COMMENT AS CHAR),
+                    CAST(TRIM(REPLACE(c.EXTRA, 'auto_increment', '')) AS CHAR)
+                FROM INFORMATION_SCHEMA.COLUMNS c
+                LEFT JOIN INFORMATION_SCHEMA.KEY_COLUMN_USAGE k
+                    ON k.TABLE_SCHEMA = c.TABLE_SCHEMA AND k.TABLE_NAME = c.TABLE_NAME
+                    AND k.COLUMN_NAME = c.COLUMN_NAME AND k.CONSTRAINT_NAME = 'PRIMARY'
+                WHERE c.TABLE_SCHEMA = DATABASE() AND c.TABLE_NAME = ?
+                ORDER BY c.ORDINAL_POSITION",
+            )
+            .bind(table)
+            .map(|row: MySqlRow| Detail {
+                column: Column {
+                    name: row.get(0),
+                    data_type: row.get(1),
+                    nullable: row.get::<i64, _>(2) != 0,
+                    default: row.get(3),
+                    pk: row.get::<i64, _>(4) as u32,
+                    auto_increment: row.get::<i64, _>(5) != 0,
+                },
+                collation: row.get(6),
+                comment: row
+                    .get::<Option<String>, _>(7)
+                    .filter(|comment| !comment.is_empty()),
+                extra: row
+                    .get::<Option<String>, _>(8)
+                    .filter(|extra| !extra.is_empty()),
+            })
+            .fetch_all(&mut **conn)
+            .await?),
+        }
+    }
+
+    /// Lists the indexes of `table`, those behind its keys and constraints included.
+    pub async fn indexes(&mut self, table: &str) -> Result<Vec<Index>> {
+        match self {
+            Self::SQLite(conn) => {
+                let mut indexes = sqlx::query(
+                    "SELECT l.name, l.\"unique\", l.origin = 'pk', l.partial, s.sql
+                    FROM pragma_index_list(?) l
+                    LEFT JOIN sqlite_schema s ON s.type = 'index' AND s.name = l.name
+                    ORDER BY l.origin = 'pk' DESC, l.name",
+                )
+                .bind(table)
+                .map(|row: SqliteRow| {
+                    let sql: Option<String> = row.get(4);
+                    Index {
+                        name: row.get(0),
+                        columns: Vec::new(),
+                        unique: row.get(1),
+                        primary: row.get(2),
+                        partial: sql
+                            .as_ref()
+                            .filter(|_| row.get::<bool, _>(3))
+                            .and_then(|sql| {
+                                let at = sql.to_ascii_uppercase().rfind(" WHERE ")?;
+                                Some(sql[at + 7..].trim().to_string())
+                            }),
+                        sql,
+                    }
+                })
+                .fetch_all(&mut **conn)
+                .await?;
+
+                for index in &mut indexes {
+                    index.columns = sqlx::query_scalar::<_, Option<String>>(
+                        "SELECT name FROM pragma_index_info(?) ORDER BY seqno",
+                    )
+                    .bind(&index.name)
+                    .fetch_all(&mut **conn)
+                    .await?
+                    .into_iter()
+                    .map(|column| column.unwrap_or_else(|| EXPRESSION.into()))
+                    .collect();
+                }
+
+                // A key aliasing the rowid needs no index of its own, but is one.
+                if !indexes.iter().any(|index| index.primary) {
+                    let key: Vec<String> = sqlx::query_scalar(
+                        "SELECT name FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk",
+                    )
+                    .bind(table)
+                    .fetch_all(&mut **conn)
+                    .await?;
+
+                    if !key.is_empty() {
+                        indexes.insert(
+                            0,
+                            Index {
+                                name: "rowid".into(),
+                                columns: key,
+                                unique: true,
+                                primary: true,
+                                partial: None,
+                                sql: None,
+                            },
+                        );
+                    }
+                }
+
+                Ok(indexes)
+            }
+            Self::MySQL(conn) => {
+                let rows: Vec<(String, bool, String)> = sqlx::query(
+                    "SELECT CAST(INDEX_NAME AS CHAR), NON_UNIQUE = 0,
+                        CAST(COALESCE(COLUMN_NAME, ?) AS CHAR)
+                    FROM INFORMATION_SCHEMA.STATISTICS
+                    WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?
+                    ORDER BY INDEX_NAME = 'PRIMARY' DESC, INDEX_NAME, SEQ_IN_INDEX",
+                )
+                .bind(EXPRESSION)
+                .bind(table)
+                .map(|row: MySqlRow| (row.get(0), row.get::<i64, _>(1) != 0, row.get(2)))
+                .fetch_all(&mut **conn)
+                .await?;
+
+                let mut indexes: Vec<Index> = Vec::new();
+                for (name, unique, column) in rows {
+                    match indexes.last_mut() {
+                        Some(index) if index.name == name => index.columns.push(column),
+                        _ => indexes.push(Index {
+                            primary: name == "PRIMARY",
+                            name,
+                            columns: vec![column],
+                            unique,
+                            partial: None,
+                            sql: None,
+                        }),
+                    }
+                }
+
+                Ok(indexes)
+            }
+        }
+    }
+
+    /// Lists the foreign keys of `table`, with the columns they reference.
+    pub async fn foreign_keys(&mut self, table: &str) -> Result<Vec<ForeignKey>> {
+        let rows: Vec<(String, String, String, Option<String>, String, String)> = match self {
+            Self::SQLite(conn) => {
+                sqlx::query(
+                    "SELECT CAST(id AS TEXT), \"from\", \"table\", \"to\", on_update, on_delete
+                    FROM pragma_foreign_key_list(?)
+                    ORDER BY id, seq",
+                )
+                .bind(table)
+                .map(|row: SqliteRow| {
+                    (
+                        row.get(0),
+                        row.get(1),
+                        row.get(2),
+                        row.get(3),
+                        row.get(4),
+                        row.get(5),
+                    )
+                })
+                .fetch_all(&mut **conn)
+                .await?
+            }
+            Self::MySQL(conn) => {
+                sqlx::query(
+                    "SELECT CAST(k.CONSTRAINT_NAME AS CHAR), CAST(k.COLUMN_NAME AS CHAR),
+                        CAST(k.REFERENCED_TABLE_NAME AS CHAR),
+                        CAST(k.REFERENCED_COLUMN_NAME AS CHAR),
+                        CAST(r.UPDATE_RULE AS CHAR), CAST(r.DELETE_RULE AS CHAR)
+                    FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE k
+                    JOIN INFORMATION_SCHEMA.REFERENTIAL_CONSTRAINTS r
+                        ON r.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA
+                        AND r.CONSTRAINT_NAME = k.CONSTRAINT_NAME AND r.TABLE_NAME = k.TABLE_NAME
+                    WHERE k.TABLE_SCHEMA = DATABASE() AND k.TABLE_NAME = ?
+                    ORDER BY k.CONSTRAINT_NAME, k.ORDINAL_POSITION",
+                )
+                .bind(table)
+                .map(|row: MySqlRow| {
+                    (
+                        row.get(0),
+                        row.get(1),
+                        row.get(2),
+                        row.get(3),
+                        row.get(4),
+                        row.get(5),
+                    )
+                })
+                .fetch_all(&mut **conn)
+                .await?
+            }
+        };
+
+        let mut keys: Vec<ForeignKey> = Vec::new();
+        let mut implicit = Vec::new();
+        for (name, column, referenced, to, on_update, on_delete) in rows {
+            match keys.last_mut() {
+                Some(key) if key.name == name => key.columns.push(column),
+                _ => keys.push(ForeignKey {
+                    name,
+                    columns: vec![column],
+                    table: referenced,
+                    references: Vec::new(),
+                    on_update,
+                    on_delete,
+                }),
+            }
+
+            let key = keys.len() - 1;
+            match to {
+                Some(to) => keys[key].references.push(to),
+                None if !implicit.contains(&key) => implicit.push(key),
+                None => (),
+            }
+        }
+
+        // SQLite lets a foreign key leave out the columns it references, which are then
+        // the primary key of the referenced table.
+        for key in implicit {
+            let columns = self.columns(&keys[key].table).await?;
+            keys[key].references = primary_key(&columns);
+        }
+
+        Ok(keys)
+    }
+
+    /// The foreign keys of every table that reference `table`, with the table each
+    /// belongs to.
+    pub async fn referencing(&mut self, table: &str) -> Result<Vec<(String, ForeignKey)>> {
+        let children: Vec<String> = match self {
+            Self::SQLite(conn) => {
+                sqlx::query_scalar(
+                    "SELECT DISTINCT m.name FROM sqlite_schema m
+                    JOIN pragma_foreign_key_list(m.name) f
+                    WHERE m.type = 'table' AND f.\"table\" = ? COLLATE NOCASE
+                    ORDER BY m.name",
+                )
+                .bind(table)
+                .fetch_all(&mut **conn)
+                .await?
+            }
+            Self::MySQL(conn) => sqlx::query_scalar(
+                "SELECT DISTINCT CAST(TABLE_NAME AS CHAR) FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE
+                    WHERE TABLE_SCHEMA = DATABASE() AND REFERENCED_TABLE_SCHEMA = DATABASE()
+                        AND REFERENCED_TABLE_NAME = ?
+                    ORDER BY 1",
+            )
+            .bind(table)
+            .fetch_all(&mut **conn)
+            .await?,
+        };
+
+        let mut keys = Vec::new();
+        for child in children {
+            for key in self.foreign_keys(&child).await? {
+                if key.table.eq_ignore_ascii_case(table) {
+                    keys.push((child.clone(), key));
+                }
+            }
+        }
+
+        Ok(keys)
+    }
+
+    /// Counts, for each foreign key referencing `table`, the rows pointing at the row
+    /// with these values by column name.
+    pub async fn dependents(
+        &mut self,
+        table: &str,
+        row: &[(String, Option<String>)],
+    ) -> Result<Vec<Dependent>> {
+        let dialect = self.dialect();
+        let mut dependents = Vec::new();
+
+        for (child, foreign_key) in self.referencing(table).await? {
+            let values: Option<Vec<Option<String>>> = foreign_key
+                .references
+                .iter()
+                .map(|column| {
+                    row.iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(column))
+                        .and_then(|(_, value)| value.clone())
+                        .map(Some)
+                })
+                .collect();
+            let key = values.map(|values| Key {
+                columns: foreign_key.columns.clone(),
+                values,
+            });
+
+            let count = match &key {
+                Some(key) => {
+                    self.count(&child, Some(&edit::condition(dialect, key)))
+                        .await?
+                }
+                None => 0,
+            };
+
+            dependents.push(Dependent {
+                table: child,
+                foreign_key,
+                key,
+                count,
+            });
+        }
+
+        Ok(dependents)
+    }
+
+    /// Lists the triggers, then on MySQL the procedures, functions and events, each
+    /// by name.
+    pub async fn objects(&mut self) -> Result<Vec<Object>> {
+        match self {
+            Self::SQLite(conn) => Ok(sqlx::query(
+                "SELECT name, tbl_name, sql FROM sqlite_schema
+                WHERE type = 'trigger' AND sql IS NOT NULL
+                ORDER BY name",
+            )
+            .map(|row: SqliteRow| {
+                let definition: String = row.get(2);
+                Object {
+                    kind: ObjectKind::Trigger,
+                    name: row.get(0),
+                    table: Some(row.get(1)),
+                    timing: trigger_timing(&definition),
+                    definer: None,
+                    definition,
+                }
+            })
+            .fetch_all(&mut **conn)
+            .await?),
+            Self::MySQL(conn) => {
+                let mut objects = sqlx::query(
+                    "SELECT CAST(TRIGGER_NAME AS CHAR), CAST(EVENT_OBJECT_TABLE AS CHAR),
+                        CAST(CONCAT(ACTION_TIMING, ' ', EVENT_MANIPULATION) AS CHAR),
+                        CAST(DEFINER AS CHAR), CAST(ACTION_STATEMENT AS CHAR)
+                    FROM INFORMATION_SCHEMA.TRIGGERS
+                    WHERE TRIGGER_SCHEMA = DATABASE()
+                    ORDER BY TRIGGER_NAME",
+                )
+                .map(|row: MySqlRow| Object {
+                    kind: ObjectKind::Trigger,
+                    name: row.get(0),
+                    table: Some(row.get(1)),
+                    timing: row.get(2),
+                    definer: row.get(3),
+                    definition: row.get(4),
+                })
+                .fetch_all(&mut **conn)
+                .await?;
+
+                objects.extend(
+                    sqlx::query(
+                        "SELECT CAST(ROUTINE_NAME AS CHAR), CAST(ROUTINE_TYPE AS CHAR),
+                            CAST(DTD_IDENTIFIER AS CHAR), CAST(DEFINER AS CHAR),
+                            CAST(ROUTINE_DEFINITION AS CHAR)
+                        FROM INFORMATION_SCHEMA.ROUTINES
+                        WHERE ROUTINE_SCHEMA = DATABASE()
+                        ORDER BY ROUTINE_TYPE DESC, ROUTINE_NAME",
+                    )
+                    .map(|row: MySqlRow| Object {
+                        kind: if row.get::<String, _>(1) == "FUNCTION" {
+                            ObjectKind::Function
+                        } else {
+                            ObjectKind::Procedure
+                        },
+                        name: row.get(0),
+                        table: None,
+                        timing: row.get::<Option<String>, _>(2).unwrap_or_default(),
+                        definer: row.get(3),
+                        // Left out without the privileges to see it.
+                        definition: row.get::<Option<String>, _>(4).unwrap_or_default(),
+                    })
+                    .fetch_all(&mut **conn)
+                    .await?,
+                );
+
+                objects.extend(
+                    sqlx::query(
+                        "SELECT CAST(EVENT_NAME AS CHAR),
+                            CAST(IF(EVENT_TYPE = 'RECURRING',
+                                CONCAT('EVERY ', INTERVAL_VALUE, ' ', INTERVAL_FIELD,
+                                    IFNULL(CONCAT(' STARTS ', STARTS), ''),
+                                    IFNULL(CONCAT(' ENDS ', ENDS), '')),
+                                CONCAT('AT ', EXECUTE_AT)) AS CHAR),
+                            CAST(STATUS AS CHAR), CAST(DEFINER AS CHAR),
+                            CAST(EVENT_DEFINITION AS CHAR)
+                        FROM INFORMATION_SCHEMA.EVENTS
+                        WHERE EVENT_SCHEMA = DATABASE()
+                        ORDER BY EVENT_NAME",
+                    )
+                    .map(|row: MySqlRow| Object {
+                        kind: ObjectKind::Event,
+                        name: row.get(0),
+                        table: None,
+                        timing: format!(
+                            "{} ({})",
+                            row.get::<String, _>(1),
+                            row.get::<String, _>(2)
+                        ),
+                        definer: row.get(3),
+                        definition: row.get(4),
+                    })
+                    .fetch_all(&mut **conn)
+                    .await?,
+                );
+
+                Ok(objects)
+            }
+        }
+    }
+
+    /// The statements creating `table` or view, then its indexes and triggers.
+    pub async fn create_statements(&mut self, table: &str) -> Result<Vec<String>> {
+        match self {
+            Self::SQLite(conn) => Ok(sqlx::query_scalar(
+                "SELECT sql FROM sqlite_schema
+                WHERE tbl_name = ? AND sql IS NOT NULL
+                ORDER BY type = 'trigger', type = 'index', name",
+            )
+            .bind(table)
+            .fetch_all(&mut **conn)
+            .await?),
+            Self::MySQL(conn) => {
+                let is_view = sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM INFORMATION_SCHEMA.VIEWS
+                    WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?",
+                )
+                .bind(table)
+                .fetch_one(&mut **conn)
+                .await?
+                    > 0;
+                let triggers: Vec<String> = sqlx::query_scalar(
+                    "SELECT CAST(TRIGGER_NAME AS CHAR) FROM INFORMATION_SCHEMA.TRIGGERS
+                    WHERE EVENT_OBJECT_SCHEMA = DATABASE() AND EVENT_OBJECT_TABLE = ?
+                    ORDER BY ACTION_ORDER",
+                )
+                .bind(table)
+                .fetch_all(&mut **conn)
+                .await?;
+
+                let quote = |ident: &str| Dialect::MySQL.quote(ident);
+                let mut shows = vec![(
+                    format!(
+                        "SHOW CREATE {} {}",
+                        if is_view { "VIEW" } else { "TABLE" },
+                        quote(table)
+                    ),
+                    1,
+                )];
+                shows.extend(
+                    triggers
+                        .iter()
+                        .map(|trigger| (format!("SHOW CREATE TRIGGER {}", quote(trigger)), 2)),
+                );
+
+                // Not every SHOW statement can be prepared, so these go as plain text.
+                let mut statements = Vec::new();
+                for (show, column) in shows {
+                    let mut rows = sqlx::raw_sql(&show).fetch(&mut **conn);
+                    if let Some(row) = rows.try_next().await? {
+                        let bytes: Vec<u8> = row.try_get_unchecked(column)?;
+                        statements.push(String::from_utf8_lossy(&bytes).into_owned());
+                    }
+                }
+
+                Ok(statements)
+            }
+        }
+    }
+
+    /// Whether rows of `table` carry a SQLite rowid.
+    pub async fn has_rowid(&mut self, table: &str) -> Result<bool> {
+        match self {
+            Self::SQLite(conn) => Ok(!sqlx::query_scalar::<_, String>(
+                "SELECT sql FROM sqlite_schema WHERE type = 'table' AND name = ?",
+            )
+            .bind(table)
+            .fetch_one(&mut **conn)
+            .await?
+            .to_uppercase()
+            .contains("WITHOUT ROWID")),
+            Self::MySQL(_) => Ok(false),
+        }
+    }
+}
+
+/// `BEFORE INSERT` and the like, read from a SQLite `CREATE TRIGGER` statement.
+fn trigger_timing(sql: &str) -> String {
+    let words: Vec<String> = sql
+        .split_whitespace()
+        .map(|word| word.to_uppercase())
+        .take_while(|word| word != "ON")
+        .collect();
+
+    let timing = if words.iter().any(|word| word == "AFTER") {
+        "AFTER"
+    } else if words.iter().any(|word| word == "INSTEAD") {
+        "INSTEAD OF"
+    } else {
+        "BEFORE"
+    };
+    let event = words
+        .iter()
+        .rev()
+        .find(|word| matches!(word.as_str(), "INSERT" | "UPDATE" | "DELETE"))
+        .map_or("", |word| word.as_str());
+
+    format!("{} {}", timing, event)
+}
+
+/// The collating sequence of a SQLite column, which no pragma reports.
+fn collation(db: *mut sqlite3, table: &str, column: &str) -> Option<String> {
+    let (table, column) = (CString::new(table).ok()?, CString::new(column).ok()?);
+    let mut data_type = ptr::null();
+    let mut collation = ptr::null();
+    let (mut not_null, mut pk, mut autoinc) = (0, 0, 0);
+
+    // SAFETY: `db` stays locked by the caller, and the collation name SQLite returns
+    // is copied before the next call can invalidate it.
+    unsafe {
+        if sqlite3_table_column_metadata(
+            db,
+            ptr::null(),
+            table.as_ptr(),
+            column.as_ptr(),
+            &mut data_type,
+            &mut collation,
+            &mut not_null,
+            &mut pk,
+            &mut autoinc,
+        ) != SQLITE_OK
+            || collation.is_null()
+        {
+            return None;
+        }
+
+        Some(CStr::from_ptr(collation).to_string_lossy().into_owned())
+    }
+}
+
+/// A lone INTEGER PRIMARY KEY aliases the rowid and is filled in automatically.
+fn mark_rowid_alias(columns: &mut [Column]) {
+    if columns.iter().filter(|column| column.pk > 0).count() == 1
+        && let Some(column) = columns.iter_mut().find(|column| column.pk > 0)
+        && column.data_type.eq_ignore_ascii_case("INTEGER")
+    {
+        column.auto_increment = true;
+    }
+}
+
+/// Names of the primary key columns, in key order.
+pub fn primary_key(columns: &[Column]) -> Vec<String> {
+    let mut key: Vec<&Column> = columns.iter().filter(|column| column.pk > 0).collect();
+    key.sort_by_key(|column| column.pk);
+    key.into_iter().map(|column| column.name.clone()).collect()
+}
+
+impl Connection {
+    /// Row counts and sizes of the tables, by name. SQLite reads the rows from the
+    /// estimates `ANALYZE` left, or else from `known`, counting them only for tables
+    /// seen for the first time, and measures sizes through `dbstat` when built with
+    /// it; MySQL gives its own estimates.
+    pub async fn table_stats(
+        &mut self,
+        known: &HashMap<String, TableStats>,
+    ) -> Result<HashMap<String, TableStats>> {
+        match self {
+            Self::SQLite(conn) => {
+                let sizes: HashMap<String, i64> = sqlx::query(
+                    "SELECT m.tbl_name, SUM(d.pgsize)
+                    FROM dbstat d JOIN sqlite_schema m ON m.name = d.name
+                    GROUP BY m.tbl_name",
+                )
+                .map(|row: SqliteRow| (row.get(0), row.get(1)))
+                .fetch_all(&mut **conn)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+                // The first number of a table's statistics is its row count.
+                let estimates: HashMap<String, u64> =
+                    sqlx::query("SELECT tbl, stat FROM sqlite_stat1")
+                        .map(|row: SqliteRow| (row.get(0), row.get(1)))
+                        .fetch_all(&mut **conn)
+                        .await
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|(table, stat): (String, String)| {
+                            let rows = stat.split_whitespace().next()?.parse().ok()?;
+                            Some((table, rows))
+                        })
+                        .collect();
+
+                let mut stats = HashMap::new();
+                for table in self.tables().await? {
+                    let rows = match (estimates.get(&table), known.get(&table)) {
+                        (Some(&rows), _) => rows,
+                        (
+                            None,
+                            Some(TableStats {
+                                rows: Some(rows), ..
+                            }),
+                        ) => *rows,
+                        (None, _) => self.count(&table, None).await?,
+                    };
+                    let bytes = sizes.get(&table).map(|&bytes| bytes as u64);
+                    stats.insert(
+                        table,
+                        TableStats {
+                            rows: Some(rows),
+                            bytes,
+                        },
+                    );
+                }
+
+                Ok(stats)
+            }
+            Self::MySQL(conn) => Ok(sqlx::query(
+                "SELECT CAST(TABLE_NAME AS CHAR), TABLE_ROWS,
+                    CAST(DATA_LENGTH + INDEX_LENGTH AS UNSIGNED)
+                FROM INFORMATION_SCHEMA.TABLES
+                WHERE TABLE_SCHEMA = DATABASE() AND TABLE_TYPE = 'BASE TABLE'",
+            )
+            .map(|row: MySqlRow| {
+                (
+                    row.get(0),
+                    TableStats {
+                        rows: row.get(1),
+                        bytes: row.get(2),
+                    },
+                )
+            })
+            .fetch_all(&mut **conn)
+            .await?
+            .into_iter()
+            .collect()),
+        }
+    }
+}
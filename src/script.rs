@@ -0,0 +1,645 @@
+use crate::{
+    db::{Connection, Dialect, Fetched, Item, Outcome, ResultSet, Values},
+    job::{Output, Reply},
+};
+use color_eyre::Result;
+use futures_util::{TryStreamExt, stream::BoxStream};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+pub enum Status {
+    Done(Outcome),
+    Failed(String),
+    Skipped,
+}
+
+pub struct StatementResult {
+    pub sql: String,
+    pub status: Status,
+    pub duration: Duration,
+}
+
+#[derive(PartialEq)]
+enum State {
+    Normal,
+    Quoted(char),
+    LineComment,
+    BlockComment,
+}
+
+/// Splits a SQL script into its statements.
+///
+/// Quoted strings and identifiers and `--`/`#`/`/* */` comments are honoured, and
+/// on MySQL the client's `DELIMITER` command. On SQLite the `BEGIN ... END` body of
+/// a `CREATE TRIGGER` is kept in one piece.
+pub fn split(script: &str, dialect: Dialect) -> Vec<String> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut statements = Vec::new();
+    let mut delimiter: Vec<char> = vec![';'];
+    let mut current = String::new();
+    let mut has_code = false;
+    let mut state = State::Normal;
+
+    let mut word = String::new();
+    let mut words = 0;
+    let mut is_create = false;
+    let mut is_trigger = false;
+    let mut depth = 0usize;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if dialect == Dialect::MySQL
+            && state == State::Normal
+            && !has_code
+            && at_line_start(&chars, i)
+        {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |n| i + n);
+            let line: String = chars[i..end].iter().collect();
+            let mut parts = line.split_whitespace();
+
+            if parts
+                .next()
+                .is_some_and(|cmd| cmd.eq_ignore_ascii_case("DELIMITER"))
+                && let Some(token) = parts.next()
+            {
+                delimiter = token.chars().collect();
+                current.clear();
+                i = end;
+                continue;
+            }
+        }
+
+        match state {
+            State::Normal => {
+                if c.is_alphanumeric() || c == '_' {
+                    word.push(c);
+                } else if !word.is_empty() {
+                    if dialect == Dialect::SQLite {
+                        track_word(
+                            &word,
+                            &mut words,
+                            &mut is_create,
+                            &mut is_trigger,
+                            &mut depth,
+                        );
+                    }
+                    word.clear();
+                }
+
+                if depth == 0 && chars[i..].starts_with(&delimiter) {
+                    if has_code {
+                        statements.push(current.trim().to_string());
+                    }
+                    current.clear();
+                    has_code = false;
+                    words = 0;
+                    is_create = false;
+                    is_trigger = false;
+                    i += delimiter.len();
+                    continue;
+                }
+
+                match c {
+                    '\'' | '"' | '`' => {
+                        state = State::Quoted(c);
+                        has_code = true;
+                    }
+                    '#' if dialect == Dialect::MySQL => state = State::LineComment,
+                    '-' if line_comment(&chars, i, dialect) => state = State::LineComment,
+                    '/' if chars.get(i + 1) == Some(&'*') => {
+                        state = State::BlockComment;
+                        current.push(c);
+                        i += 1;
+                    }
+                    c if !c.is_whitespace() => has_code = true,
+                    _ => (),
+                }
+            }
+            State::Quoted(quote) => {
+                if c == '\\' && dialect == Dialect::MySQL && quote != '`' {
+                    current.push(c);
+                    i += 1;
+                    if let Some(&escaped) = chars.get(i) {
+                        current.push(escaped);
+                    }
+                    i += 1;
+                    continue;
+                } else if c == quote {
+                    if chars.get(i + 1) == Some(&quote) {
+                        current.push(c);
+                        i += 1;
+                    } else {
+                        state = State::Normal;
+                    }
+                }
+            }
+            State::LineComment => {
+                if c == '\n' {
+                    state = State::Normal;
+                }
+            }
+            State::BlockComment => {
+                if c == '*' && chars.get(i + 1) == Some(&'/') {
+                    state = State::Normal;
+                    current.push(c);
+                    i += 1;
+                }
+            }
+        }
+
+        current.push(chars[i]);
+        i += 1;
+    }
+
+    if has_code {
+        statements.push(current.trim().to_string());
+    }
+
+    statements
+}
+
+/// Whether a `--` comment starts at `i`. MySQL wants whitespace after the dashes,
+/// so that `1--1` stays arithmetic.
+fn line_comment(chars: &[char], i: usize, dialect: Dialect) -> bool {
+    chars[i..].starts_with(&['-', '-'])
+        && (dialect == Dialect::SQLite
+            || chars
+                .get(i + 2)
+                .is_none_or(|c| c.is_whitespace() || c.is_control()))
+}
+
+fn at_line_start(chars: &[char], i: usize) -> bool {
+    chars[..i]
+        .iter()
+        .rev()
+        .take_while(|&&c| c != '\n')
+        .all(|c| c.is_whitespace())
+}
+
+fn track_word(
+    word: &str,
+    words: &mut usize,
+    is_create: &mut bool,
+    is_trigger: &mut bool,
+    depth: &mut usize,
+) {
+    *words += 1;
+
+    if *words == 1 {
+        *is_create = word.eq_ignore_ascii_case("CREATE");
+    } else if *is_create && !*is_trigger && *depth == 0 && word.eq_ignore_ascii_case("TRIGGER") {
+        *is_trigger = true;
+    } else if *is_trigger {
+        if word.eq_ignore_ascii_case("BEGIN") || word.eq_ignore_ascii_case("CASE") {
+            *depth += 1;
+        } else if word.eq_ignore_ascii_case("END") {
+            *depth = depth.saturating_sub(1);
+        }
+    }
+}
+
+/// The target of a statement that destroys data wholesale: a `DROP`, a `TRUNCATE`,
+/// or a `DELETE`/`UPDATE` without a `WHERE`, behind a `WITH` clause or not.
+pub fn destructive(statement: &str, dialect: Dialect) -> Option<String> {
+    let all = tokens(statement, dialect);
+    // The common table expressions of a `WITH` are all in parentheses, so the
+    // statement proper starts at the first keyword outside them.
+    let start = match all.first() {
+        Some((token, false)) if token.eq_ignore_ascii_case("WITH") => {
+            let mut depth = 0i32;
+            all.iter().position(|(token, quoted)| {
+                match token.as_str() {
+                    "(" => depth += 1,
+                    ")" => depth -= 1,
+                    _ => (),
+                }
+                depth == 0
+                    && !quoted
+                    && ["SELECT", "INSERT", "REPLACE", "DELETE", "UPDATE", "VALUES"]
+                        .iter()
+                        .any(|keyword| token.eq_ignore_ascii_case(keyword))
+            })?
+        }
+        _ => 0,
+    };
+    let tokens = &all[start..];
+    let is = |idx: usize, keyword: &str| {
+        tokens
+            .get(idx)
+            .is_some_and(|(token, quoted)| !quoted && token.eq_ignore_ascii_case(keyword))
+    };
+    let unfiltered = || {
+        let mut depth = 0i32;
+        !tokens.iter().any(|(token, quoted)| {
+            match token.as_str() {
+                "(" => depth += 1,
+                ")" => depth -= 1,
+                _ => (),
+            }
+            depth == 0 && !quoted && token.eq_ignore_ascii_case("WHERE")
+        })
+    };
+    let skip = |mut idx: usize, words: &[&str]| {
+        while words.iter().any(|word| is(idx, word)) {
+            idx += 1;
+        }
+        idx
+    };
+
+    let target = if is(0, "DROP") {
+        skip(1, &["TEMPORARY", "TEMP", "IF", "EXISTS"]) + 1
+    } else if is(0, "TRUNCATE") {
+        skip(1, &["TABLE"])
+    } else if is(0, "DELETE") && unfiltered() {
+        skip(1, &["LOW_PRIORITY", "QUICK", "IGNORE", "FROM"])
+    } else if is(0, "UPDATE") && unfiltered() {
+        match skip(1, &["LOW_PRIORITY", "IGNORE"]) {
+            // SQLite's `UPDATE OR REPLACE` and the like.
+            idx if is(idx, "OR") => idx + 2,
+            idx => idx,
+        }
+    } else {
+        return None;
+    };
+
+    // A qualified `schema.table` is confirmed by its table name.
+    let mut idx = skip(target, &["IF", "EXISTS"]);
+    while tokens.get(idx + 1).is_some_and(|(token, _)| token == ".") {
+        idx += 2;
+    }
+
+    tokens.get(idx).map(|(token, _)| token.clone())
+}
+
+/// Words, unquoted identifiers and punctuation of a statement, with whether each was
+/// quoted; string literals become `'` and comments are dropped.
+pub fn tokens(statement: &str, dialect: Dialect) -> Vec<(String, bool)> {
+    let chars: Vec<char> = statement.chars().collect();
+    let mut tokens = Vec::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if line_comment(&chars, i, dialect) || (c == '#' && dialect == Dialect::MySQL) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if matches!(c, '\'' | '"' | '`' | '[') {
+            let close = if c == '[' { ']' } else { c };
+            let mut text = String::new();
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == close {
+                    // A doubled quote stands for itself.
+                    if close != ']' && chars.get(i + 1) == Some(&close) {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                text.push(chars[i]);
+                i += 1;
+            }
+            i += 1;
+
+            tokens.push(match c {
+                '\'' => ("'".into(), true),
+                _ => (text, true),
+            });
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            tokens.push((chars[start..i].iter().collect(), false));
+        } else {
+            tokens.push((c.to_string(), false));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// How result sets are loaded: in batches of `batch` rows, up to `max_bytes` in total.
+///
+/// When the last statement has rows left after its first batch, its stream stays open
+/// as a cursor: every message on `demand` loads one more batch, sent back on `rows`.
+pub struct Fetch {
+    pub id: u64,
+    pub batch: usize,
+    pub max_bytes: usize,
+    pub demand: UnboundedReceiver<()>,
+    pub rows: UnboundedSender<Batch>,
+}
+
+/// Rows loaded by an open cursor.
+pub struct Batch {
+    pub id: u64,
+    pub rows: Vec<Values>,
+    pub fetched: Fetched,
+    pub error: Option<String>,
+}
+
+/// Runs every statement in order, skipping the remainder after the first failure
+/// unless `continue_on_error` is set.
+///
+/// If the last result set is left open as a cursor, the results are sent through
+/// `reply` right away and the cursor is served until closed.
+pub async fn run(
+    conn: &mut Connection,
+    statements: Vec<String>,
+    continue_on_error: bool,
+    mut fetch: Fetch,
+    reply: &mut Reply,
+) -> Vec<StatementResult> {
+    let mut results = Vec::with_capacity(statements.len());
+    let mut failed = false;
+    let count = statements.len();
+
+    for (idx, sql) in statements.into_iter().enumerate() {
+        if failed && !continue_on_error {
+            results.push(StatementResult {
+                sql,
+                status: Status::Skipped,
+                duration: Duration::ZERO,
+            });
+            continue;
+        }
+
+        let start = Instant::now();
+        let mut stream = conn.stream(&sql);
+        let mut set = None;
+        let mut affected = 0;
+
+        let last = idx + 1 == count;
+        let mut loaded = load(&mut stream, &mut set, &mut affected, &fetch, 0).await;
+        // Only the last result set stays open as a cursor, so earlier ones are loaded
+        // in full, up to the memory cap.
+        while !last && matches!(loaded, Ok(Fetched::More)) {
+            loaded = load(&mut stream, &mut set, &mut affected, &fetch, 0).await;
+        }
+
+        let status = match loaded {
+            Ok(fetched) => match set {
+                Some(mut set) => {
+                    set.fetched = fetched;
+                    Status::Done(Outcome::Rows(set))
+                }
+                None => Status::Done(Outcome::Affected(affected)),
+            },
+            Err(err) => {
+                failed = true;
+                Status::Failed(err.to_string())
+            }
+        };
+        let duration = start.elapsed();
+
+        let cursor = match &status {
+            Status::Done(Outcome::Rows(set)) if set.fetched == Fetched::More => Some(set.bytes),
+            _ => None,
+        };
+
+        results.push(StatementResult {
+            sql: sql.clone(),
+            status,
+            duration,
+        });
+
+        if let Some(bytes) = cursor {
+            reply.send(Ok(Output::Script(results)));
+            serve(&mut stream, &mut fetch, bytes).await;
+            return Vec::new();
+        }
+    }
+
+    results
+}
+
+/// Pulls rows into `set` until a full batch is loaded or the memory cap is reached,
+/// counting `used` bytes loaded before.
+async fn load(
+    stream: &mut BoxStream<'_, Result<Item>>,
+    set: &mut Option<ResultSet>,
+    affected: &mut u64,
+    fetch: &Fetch,
+    used: usize,
+) -> Result<Fetched> {
+    let mut count = 0;
+
+    while let Some(item) = stream.try_next().await? {
+        match item {
+            Item::Affected(n) => *affected += n,
+            Item::Row(columns, values) => {
+                let set = set.get_or_insert_with(|| ResultSet::new(columns.unwrap_or_default()));
+                set.push(values);
+                count += 1;
+
+                if used + set.bytes >= fetch.max_bytes {
+                    return Ok(Fetched::Capped);
+                } else if count >= fetch.batch {
+                    return Ok(Fetched::More);
+                }
+            }
+        }
+    }
+
+    Ok(Fetched::Complete)
+}
+
+async fn serve(stream: &mut BoxStream<'_, Result<Item>>, fetch: &mut Fetch, mut used: usize) {
+    while fetch.demand.recv().await.is_some() {
+        let mut set = Some(ResultSet::new(Vec::new()));
+        let mut affected = 0;
+
+        let (fetched, error) = match load(stream, &mut set, &mut affected, fetch, used).await {
+            Ok(fetched) => (fetched, None),
+            Err(err) => (Fetched::Complete, Some(err.to_string())),
+        };
+        let set = set.unwrap_or_else(|| ResultSet::new(Vec::new()));
+        used += set.bytes;
+
+        let _ = fetch.rows.send(Batch {
+            id: fetch.id,
+            rows: set.rows,
+            fetched,
+            error,
+        });
+
+        if fetched != Fetched::More {
+            break;
+        }
+    }
+
+    // The connection is only given up once the cursor is closed, so that stopping
+    // it cannot interrupt whatever runs on the connection next.
+    while fetch.demand.recv().await.is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_keeps_delimiters_in_quotes_and_comments() {
+        let script = "SELECT 'a;b', \"c;d\", `e;f`;\n-- g;h\nSELECT 1 /* i;j */;\n# k;l\nSELECT 2";
+        assert_eq!(
+            split(script, Dialect::MySQL),
+            [
+                "SELECT 'a;b', \"c;d\", `e;f`",
+                "-- g;h\nSELECT 1 /* i;j */",
+                "# k;l\nSELECT 2"
+            ]
+        );
+    }
+
+    #[test]
+    fn split_handles_escapes_and_doubled_quotes() {
+        assert_eq!(
+            split(r"SELECT 'it\'s;'; SELECT 'a''b;c'", Dialect::MySQL),
+            [r"SELECT 'it\'s;'", "SELECT 'a''b;c'"]
+        );
+        assert_eq!(
+            split("SELECT 'a\\'; SELECT 2", Dialect::SQLite),
+            ["SELECT 'a\\'", "SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn split_skips_statements_of_comments_alone() {
+        assert_eq!(
+            split("-- nothing;\n/* at all; */ ;;SELECT 1;", Dialect::SQLite),
+            ["SELECT 1"]
+        );
+    }
+
+    #[test]
+    fn split_wants_a_space_after_dashes_on_mysql() {
+        assert_eq!(
+            split("SELECT 1--1; SELECT 2", Dialect::MySQL),
+            ["SELECT 1--1", "SELECT 2"]
+        );
+        assert_eq!(
+            split("SELECT 1--1; SELECT 2\nSELECT 3", Dialect::SQLite),
+            ["SELECT 1--1; SELECT 2\nSELECT 3"]
+        );
+    }
+
+    #[test]
+    fn split_follows_the_delimiter_command() {
+        let script = "DELIMITER //\nCREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END//\nDELIMITER ;\nCALL p();";
+        assert_eq!(
+            split(script, Dialect::MySQL),
+            [
+                "CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END",
+                "CALL p()"
+            ]
+        );
+    }
+
+    #[test]
+    fn split_leaves_the_delimiter_command_to_mysql() {
+        // SQLite has no such command; it gets the line and reports it.
+        assert_eq!(
+            split("DELIMITER $$\nSELECT 1; SELECT 2$$", Dialect::SQLite),
+            ["DELIMITER $$\nSELECT 1", "SELECT 2$$"]
+        );
+    }
+
+    #[test]
+    fn split_keeps_trigger_bodies_whole_on_sqlite() {
+        let script = "CREATE TRIGGER t AFTER INSERT ON a BEGIN\n  UPDATE b SET n = CASE WHEN n > 0 THEN n ELSE 0 END;\n  DELETE FROM c;\nEND;\nSELECT 1;";
+        assert_eq!(
+            split(script, Dialect::SQLite),
+            [
+                "CREATE TRIGGER t AFTER INSERT ON a BEGIN\n  UPDATE b SET n = CASE WHEN n > 0 THEN n ELSE 0 END;\n  DELETE FROM c;\nEND",
+                "SELECT 1"
+            ]
+        );
+        assert_eq!(
+            split("BEGIN; SELECT 1; END;", Dialect::SQLite),
+            ["BEGIN", "SELECT 1", "END"]
+        );
+    }
+
+    #[test]
+    fn destructive_finds_the_target() {
+        let target = |sql| destructive(sql, Dialect::MySQL);
+        assert_eq!(
+            target("DROP TABLE IF EXISTS `orders`"),
+            Some("orders".into())
+        );
+        assert_eq!(
+            target("drop temporary table shop.orders"),
+            Some("orders".into())
+        );
+        assert_eq!(target("TRUNCATE TABLE orders"), Some("orders".into()));
+        assert_eq!(
+            target("DELETE LOW_PRIORITY FROM orders"),
+            Some("orders".into())
+        );
+        assert_eq!(
+            target("UPDATE IGNORE orders SET a = 1"),
+            Some("orders".into())
+        );
+        assert_eq!(
+            destructive("UPDATE OR REPLACE \"orders\" SET a = 1", Dialect::SQLite),
+            Some("orders".into())
+        );
+    }
+
+    #[test]
+    fn destructive_lets_filtered_statements_through() {
+        let target = |sql| destructive(sql, Dialect::MySQL);
+        assert_eq!(target("DELETE FROM orders WHERE id = 1"), None);
+        assert_eq!(target("UPDATE orders SET a = 'WHERE' WHERE id = 1"), None);
+        assert_eq!(target("SELECT * FROM orders"), None);
+        assert_eq!(target("-- DROP TABLE orders\nSELECT 1"), None);
+    }
+
+    #[test]
+    fn destructive_ignores_where_in_subqueries() {
+        assert_eq!(
+            destructive(
+                "UPDATE orders SET a = (SELECT max(b) FROM c WHERE c.id = 1)",
+                Dialect::SQLite
+            ),
+            Some("orders".into())
+        );
+    }
+
+    #[test]
+    fn destructive_looks_past_with() {
+        let target = |sql| destructive(sql, Dialect::SQLite);
+        assert_eq!(
+            target("WITH old AS (SELECT id FROM a WHERE id < 5) DELETE FROM orders"),
+            Some("orders".into())
+        );
+        assert_eq!(
+            target("WITH RECURSIVE n(i) AS (SELECT 1), m AS (SELECT 2) UPDATE orders SET a = 1"),
+            Some("orders".into())
+        );
+        assert_eq!(
+            target("WITH old AS (SELECT id FROM a) DELETE FROM orders WHERE id IN old"),
+            None
+        );
+        assert_eq!(target("WITH old AS (SELECT 1) SELECT * FROM old"), None);
+    }
+}
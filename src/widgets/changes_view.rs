@@ -0,0 +1,113 @@
+use crate::{changes::ChangeSet, widgets::Component};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Text},
+    widgets::{
+        Block, BorderType, List, ListItem, ListState, Padding, Paragraph, StatefulWidget, Widget,
+    },
+};
+
+#[derive(Default)]
+pub struct ChangesView {
+    pub changes: ChangeSet,
+    pub state: ListState,
+}
+
+impl Component for ChangesView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(format!(
+                " Pending changes ({}) ",
+                self.changes.changes.len()
+            ))
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list_area, _, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        if self.changes.is_empty() {
+            Paragraph::new("No pending changes.")
+                .centered()
+                .render(list_area, buf);
+        }
+
+        StatefulWidget::render(
+            List::new(
+                self.changes
+                    .changes
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, change)| {
+                        let mut lines = vec![
+                            Line::from(format!("{}. {}", idx + 1, change.title()))
+                                .style(Modifier::BOLD),
+                        ];
+                        lines.extend(change.diff().into_iter().map(|(sign, text)| {
+                            Line::styled(
+                                format!("  {} {}", sign, text),
+                                Style::default().fg(if sign == '-' {
+                                    Color::Red
+                                } else {
+                                    Color::Green
+                                }),
+                            )
+                        }));
+                        lines.push(Line::styled(
+                            format!("  {}", change.statement.preview),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                        lines.push(Line::default());
+
+                        ListItem::new(Text::from(lines))
+                    }),
+            )
+            .highlight_symbol(">> ")
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+            list_area,
+            buf,
+            &mut self.state,
+        );
+
+        Paragraph::new("[Enter] Commit all  [u] Undo last  [D] Discard all  [v] Back")
+            .style(Style::default().fg(Color::DarkGray))
+            .centered()
+            .render(hint_area, buf);
+    }
+
+    fn next(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n + 1 < self.changes.changes.len()
+        {
+            self.state.select(Some(n + 1));
+        } else if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn prev(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n > 0
+        {
+            self.state.select(Some(n - 1));
+        } else {
+            self.state.select(Some(0));
+        }
+    }
+}
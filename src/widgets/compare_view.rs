@@ -0,0 +1,152 @@
+use crate::{
+    compare::Comparison,
+    widgets::{
+        Component,
+        ddl_view::{highlight, script},
+    },
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Padding, Paragraph, Widget, Wrap},
+};
+
+/// The differences between the schema of this database and another one, side by
+/// side, with the statements bringing one in line with the other.
+#[derive(Default)]
+pub struct CompareView {
+    pub comparison: Option<Comparison>,
+    /// Whether the statements bring this database in line with the other one.
+    pub flipped: bool,
+    /// First line shown.
+    pub scroll: u16,
+}
+
+impl CompareView {
+    pub fn statements(&self) -> &[String] {
+        match &self.comparison {
+            Some(comparison) if self.flipped => &comparison.to_this,
+            Some(comparison) => &comparison.to_other,
+            None => &[],
+        }
+    }
+
+    /// The statements as a script, ready to be pasted.
+    pub fn text(&self) -> String {
+        script(self.statements())
+    }
+
+    fn lines(&self) -> usize {
+        self.comparison.as_ref().map_or(0, |comparison| {
+            comparison.differences.len().max(1) + 3 + self.text().lines().count()
+        })
+    }
+}
+
+/// `text` on one line, cut or padded to `width` characters.
+fn cell(text: &str, width: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if text.chars().count() < width {
+        format!("{:<width$}", text, width = width)
+    } else {
+        let cut: String = text.chars().take(width.saturating_sub(2)).collect();
+        format!("{}… ", cut)
+    }
+}
+
+impl Component for CompareView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match &self.comparison {
+                Some(comparison) => format!(" Schema of this database vs {} ", comparison.other),
+                None => " Schema comparison ".into(),
+            })
+            .title_bottom(Line::from(" [f] Flip direction  [y] Copy SQL ").centered())
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+        let inner = block.inner(area);
+
+        let Some(comparison) = &self.comparison else {
+            Paragraph::new("No comparison yet.")
+                .block(block)
+                .render(area, buf);
+            return;
+        };
+
+        let width = inner.width as usize;
+        let (object, side) = (width * 3 / 10, width * 7 / 20);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let dim = Style::default().fg(Color::DarkGray);
+
+        let mut lines = vec![Line::from(vec![
+            Span::styled(cell("Object", object), bold),
+            Span::styled(cell("This database", side), bold),
+            Span::styled(cell(&comparison.other, side), bold),
+        ])];
+        if comparison.differences.is_empty() {
+            lines.push(Line::styled("The schemas match.", dim));
+        }
+        for difference in &comparison.differences {
+            let color = match (&difference.here, &difference.there) {
+                (Some(_), None) => Color::Green,
+                (None, Some(_)) => Color::Red,
+                _ => Color::Yellow,
+            };
+            let side_cell = |text: &Option<String>| match text {
+                Some(text) => Span::raw(cell(text, side)),
+                None => Span::styled(cell("—", side), dim),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(cell(&difference.object, object), Style::default().fg(color)),
+                side_cell(&difference.here),
+                side_cell(&difference.there),
+            ]));
+        }
+
+        lines.push(Line::default());
+        lines.push(Line::styled(
+            if self.flipped {
+                format!(
+                    "-- Bringing this database in line with {}:",
+                    comparison.other
+                )
+            } else {
+                format!(
+                    "-- Bringing {} in line with this database:",
+                    comparison.other
+                )
+            },
+            dim,
+        ));
+        if self.statements().is_empty() {
+            lines.push(Line::styled("-- Nothing to change.", dim));
+        }
+        let text = self.text();
+        lines.extend(text.lines().map(highlight));
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+
+    fn next(&mut self) {
+        if (self.scroll as usize) + 1 < self.lines() {
+            self.scroll += 1;
+        }
+    }
+
+    fn prev(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
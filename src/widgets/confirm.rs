@@ -0,0 +1,59 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+/// A centered yes/no dialog drawn on top of the other widgets.
+pub struct Confirm {
+    pub title: String,
+    pub text: String,
+}
+
+impl Confirm {
+    pub fn new(title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            text: text.into(),
+        }
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Percentage(50)])
+            .flex(Flex::Center)
+            .areas(area);
+        // Grow with the text, which may be a whole statement to review.
+        let width = area.width.saturating_sub(4).max(1) as usize;
+        let lines: usize = self
+            .text
+            .lines()
+            .map(|line| line.chars().count().div_ceil(width).max(1))
+            .sum();
+        let [area] = Layout::vertical([Constraint::Length(lines as u16 + 8)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buf);
+
+        Paragraph::new(vec![
+            Line::from(self.text.as_str()),
+            Line::default(),
+            Line::from("[y] Yes    [n] No").centered(),
+        ])
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::bordered()
+                .title(format!(" {} ", self.title))
+                .border_type(BorderType::Rounded)
+                .padding(Padding::uniform(1))
+                .border_style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+        )
+        .render(area, buf);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    data_diff::DataDiff,
+    db,
+    widgets::{Component, TablePage, ddl_view::script},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, BorderType, Cell, Padding, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+    },
+};
+
+/// The rows differing between two tables, a page at a time.
+#[derive(Default)]
+pub struct DataDiffView {
+    pub diff: Option<DataDiff>,
+    /// Page of differing rows shown, turned in paging mode.
+    pub page: TablePage,
+    state: TableState,
+}
+
+impl DataDiffView {
+    /// Shows `diff` from its first page, `size` rows a page.
+    pub fn new(diff: DataDiff, size: u8) -> Self {
+        let per_page = size.max(1) as usize;
+        let pages = diff.rows.len().div_ceil(per_page) as u16;
+        Self {
+            diff: Some(diff),
+            page: TablePage {
+                page: pages.min(1),
+                end: pages,
+                size,
+            },
+            state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    /// Goes to the next or the previous page.
+    pub fn turn(&mut self, forward: bool) {
+        if forward {
+            self.page.next();
+        } else {
+            self.page.prev();
+        }
+        self.state.select(Some(0));
+    }
+
+    /// The statements bringing the other table in line, as a script.
+    pub fn sync_text(&self) -> String {
+        self.diff
+            .as_ref()
+            .map_or_else(String::new, |diff| script(&diff.sync))
+    }
+
+    fn shown(&self) -> usize {
+        let size = self.page.size.max(1) as usize;
+        self.diff.as_ref().map_or(0, |diff| {
+            diff.rows
+                .len()
+                .saturating_sub(self.page.page.saturating_sub(1) as usize * size)
+                .min(size)
+        })
+    }
+}
+
+impl Component for DataDiffView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match &self.diff {
+                Some(diff) => {
+                    let (here, there, changed) = diff.counts();
+                    format!(
+                        " {} vs {}: {} only here, {} only there, {} changed, {} equal{} ",
+                        diff.table,
+                        diff.other,
+                        here,
+                        there,
+                        changed,
+                        diff.same,
+                        if diff.capped {
+                            " (stopped at the memory cap)"
+                        } else {
+                            ""
+                        }
+                    )
+                }
+                None => " Row comparison ".into(),
+            })
+            .title_bottom(Line::from(" [E] Export sync statements ").centered())
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let Some(diff) = &self.diff else {
+            Paragraph::new("No comparison yet.")
+                .block(block)
+                .centered()
+                .render(area, buf);
+            return;
+        };
+        if diff.rows.is_empty() {
+            Paragraph::new(format!("All {} rows are equal.", diff.same))
+                .block(block)
+                .centered()
+                .render(area, buf);
+            return;
+        }
+
+        let size = self.page.size.max(1) as usize;
+        let start = self.page.page.saturating_sub(1) as usize * size;
+        let changed = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+
+        let rows = diff.rows.iter().skip(start).take(size).map(|row| {
+            let (mark, style) = match (&row.here, &row.there) {
+                (Some(_), None) => ("+", Style::default().fg(Color::Green)),
+                (None, Some(_)) => ("-", Style::default().fg(Color::Red)),
+                _ => ("~", Style::default()),
+            };
+            let cells = (0..diff.columns.len()).map(|idx| match (&row.here, &row.there) {
+                (Some(here), Some(there)) if row.differs(idx) => Cell::from(format!(
+                    "{} → {}",
+                    db::shown(&there[idx]),
+                    db::shown(&here[idx])
+                ))
+                .style(changed),
+                (Some(values), _) | (_, Some(values)) => Cell::from(db::shown(&values[idx])),
+                (None, None) => Cell::default(),
+            });
+            Row::new([Cell::from(mark)].into_iter().chain(cells)).style(style)
+        });
+
+        StatefulWidget::render(
+            Table::new(
+                rows,
+                [Constraint::Length(1)]
+                    .into_iter()
+                    .chain(diff.columns.iter().map(|_| Constraint::Fill(1)))
+                    .collect::<Vec<Constraint>>(),
+            )
+            .block(block)
+            .header(
+                Row::new(
+                    [""].into_iter()
+                        .chain(diff.columns.iter().map(|column| column.as_str())),
+                )
+                .style(Modifier::BOLD)
+                .bottom_margin(1),
+            )
+            .highlight_symbol(">> ")
+            .row_highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+            area,
+            buf,
+            &mut self.state,
+        );
+    }
+
+    fn next(&mut self) {
+        match self.state.selected() {
+            Some(n) if n + 1 < self.shown() => self.state.select(Some(n + 1)),
+            _ => (),
+        }
+    }
+
+    fn prev(&mut self) {
+        match self.state.selected() {
+            Some(n) if n > 0 => self.state.select(Some(n - 1)),
+            _ => (),
+        }
+    }
+}
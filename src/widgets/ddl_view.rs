@@ -0,0 +1,199 @@
+use crate::widgets::Component;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Padding, Paragraph, Widget, Wrap},
+};
+
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "AFTER",
+    "ALTER",
+    "ALWAYS",
+    "AS",
+    "ASC",
+    "AUTOINCREMENT",
+    "AUTO_INCREMENT",
+    "BEFORE",
+    "BEGIN",
+    "BY",
+    "CASCADE",
+    "CHARSET",
+    "CHECK",
+    "COLUMN",
+    "COLLATE",
+    "COMMENT",
+    "CONSTRAINT",
+    "CREATE",
+    "DEFAULT",
+    "DEFINER",
+    "DELETE",
+    "DESC",
+    "DROP",
+    "EACH",
+    "END",
+    "ENGINE",
+    "EXISTS",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "GENERATED",
+    "IF",
+    "INDEX",
+    "INSERT",
+    "INTO",
+    "KEY",
+    "MODIFY",
+    "NEW",
+    "NO",
+    "NOT",
+    "NULL",
+    "OF",
+    "OLD",
+    "ON",
+    "OR",
+    "PRIMARY",
+    "REFERENCES",
+    "RESTRICT",
+    "ROW",
+    "ROWID",
+    "SELECT",
+    "SET",
+    "STORED",
+    "TABLE",
+    "TEMP",
+    "TEMPORARY",
+    "TRIGGER",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "VIEW",
+    "VIRTUAL",
+    "WHEN",
+    "WHERE",
+    "WITH",
+    "WITHOUT",
+];
+
+/// The statements creating a table, shown highlighted.
+#[derive(Default)]
+pub struct DdlView {
+    pub table: Option<String>,
+    pub statements: Vec<String>,
+    /// First line shown.
+    pub scroll: u16,
+}
+
+impl DdlView {
+    /// The statements as a script, ready to be pasted.
+    pub fn text(&self) -> String {
+        script(&self.statements)
+    }
+
+    fn lines(&self) -> usize {
+        self.text().lines().count()
+    }
+}
+
+/// `statements` ended with semicolons and separated by blank lines. Lines of
+/// comments alone are kept as they are.
+pub(super) fn script(statements: &[String]) -> String {
+    statements
+        .iter()
+        .map(|statement| {
+            let statement = statement.trim_end().trim_end_matches(';');
+            if statement
+                .lines()
+                .all(|line| line.trim_start().starts_with("--"))
+            {
+                return format!("{}\n", statement);
+            }
+            // A trailing comment would swallow the semicolon.
+            match statement.lines().last() {
+                Some(line) if line.contains("--") => format!("{}\n;\n", statement),
+                _ => format!("{};\n", statement),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Colours keywords, strings, quoted identifiers, numbers and comments of a line.
+pub(super) fn highlight(line: &str) -> Line<'_> {
+    let mut spans = Vec::new();
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        let (len, style) = match c {
+            '\'' | '"' | '`' => (
+                rest[1..].find(c).map_or(rest.len(), |end| end + 2),
+                Style::default().fg(if c == '\'' { Color::Green } else { Color::Cyan }),
+            ),
+            '-' if rest.starts_with("--") => (rest.len(), Style::default().fg(Color::DarkGray)),
+            c if c.is_ascii_digit() => (
+                rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+                    .unwrap_or(rest.len()),
+                Style::default().fg(Color::Magenta),
+            ),
+            c if c.is_alphabetic() || c == '_' => {
+                let len = rest
+                    .find(|c: char| !c.is_alphanumeric() && c != '_')
+                    .unwrap_or(rest.len());
+                let style = if KEYWORDS.contains(&rest[..len].to_uppercase().as_str()) {
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                (len, style)
+            }
+            c => (c.len_utf8(), Style::default()),
+        };
+
+        spans.push(Span::styled(&rest[..len], style));
+        rest = &rest[len..];
+    }
+
+    Line::from(spans)
+}
+
+impl Component for DdlView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match &self.table {
+                Some(table) => format!(" CREATE statements of {} ", table),
+                None => " CREATE statements ".into(),
+            })
+            .title_bottom(Line::from(" [y] Copy to clipboard ").centered())
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let text = self.text();
+        Paragraph::new(text.lines().map(highlight).collect::<Vec<Line>>())
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+
+    fn next(&mut self) {
+        if (self.scroll as usize) + 1 < self.lines() {
+            self.scroll += 1;
+        }
+    }
+
+    fn prev(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{schema::Dependent, widgets::Component};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Cell, Padding, Row, StatefulWidget, Table, TableState},
+};
+
+/// The rows pointing at the selected row, by referencing foreign key.
+#[derive(Default)]
+pub struct DependentsView {
+    pub table: Option<String>,
+    pub dependents: Vec<Dependent>,
+    pub state: TableState,
+}
+
+impl DependentsView {
+    pub fn selected(&self) -> Option<&Dependent> {
+        self.state
+            .selected()
+            .and_then(|selected| self.dependents.get(selected))
+    }
+}
+
+impl Component for DependentsView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match &self.table {
+                Some(table) => format!(" Rows referencing this row of {} ", table),
+                None => " Rows referencing this row ".into(),
+            })
+            .title_bottom(Line::from(" [Enter] Open the referencing rows ").centered())
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let dim = Style::default().fg(Color::DarkGray);
+        let rows = self.dependents.iter().map(|dependent| {
+            let key = &dependent.foreign_key;
+            Row::new([
+                Cell::from(dependent.table.clone()),
+                Cell::from(key.name.clone()),
+                match &dependent.key {
+                    Some(matching) => Cell::from(matching.shown()),
+                    None => Cell::from(format!("{} (NULL)", key.references.join(", "))).style(dim),
+                },
+                Cell::from(dependent.count.to_string()),
+                Cell::from(key.on_delete.clone()),
+            ])
+            .style(if dependent.count > 0 {
+                Style::default()
+            } else {
+                dim
+            })
+        });
+
+        StatefulWidget::render(
+            Table::new(
+                rows,
+                [
+                    Constraint::Fill(2),
+                    Constraint::Fill(2),
+                    Constraint::Fill(4),
+                    Constraint::Length(10),
+                    Constraint::Length(12),
+                ],
+            )
+            .header(
+                Row::new(["Table", "Foreign key", "Matching", "Rows", "On delete"])
+                    .style(Modifier::BOLD)
+                    .bottom_margin(1),
+            )
+            .block(block)
+            .highlight_symbol(">> ")
+            .row_highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+            area,
+            buf,
+            &mut self.state,
+        );
+    }
+
+    fn next(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n + 1 < self.dependents.len()
+        {
+            self.state.select(Some(n + 1));
+        } else if self.state.selected().is_none() && !self.dependents.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn prev(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n > 0
+        {
+            self.state.select(Some(n - 1));
+        } else if !self.dependents.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    diagram::{self, Entity, Node},
+    widgets::Component,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::Line,
+    widgets::{
+        Block, BorderType, Widget,
+        canvas::{self, Canvas},
+    },
+};
+
+/// The tables of the database as boxes of their key columns, joined by their
+/// foreign keys.
+#[derive(Default)]
+pub struct DiagramView {
+    pub entities: Vec<Entity>,
+    /// The entities drawn: all of them, or the neighborhood of `focused`.
+    shown: Vec<usize>,
+    focused: Option<usize>,
+    nodes: Vec<Node>,
+    /// Selected node.
+    selected: usize,
+    /// Diagram cell at the top left of the view.
+    offset: (i32, i32),
+    /// Whether to bring the selected box to the middle on the next render.
+    center: bool,
+}
+
+impl DiagramView {
+    /// A diagram of every entity, with `table` selected.
+    pub fn new(entities: Vec<Entity>, table: Option<&str>) -> Self {
+        let selected =
+            table.and_then(|table| entities.iter().position(|entity| entity.table == table));
+        let mut view = Self {
+            shown: (0..entities.len()).collect(),
+            entities,
+            ..Default::default()
+        };
+        view.arrange(selected);
+        view
+    }
+
+    /// Lays out the shown entities again, keeping `entity` selected.
+    fn arrange(&mut self, entity: Option<usize>) {
+        self.nodes = diagram::layout(&self.entities, &self.shown);
+        self.selected = entity
+            .and_then(|entity| self.nodes.iter().position(|node| node.entity == entity))
+            .unwrap_or_default();
+        self.center = true;
+    }
+
+    pub fn selected_table(&self) -> Option<&str> {
+        self.nodes
+            .get(self.selected)
+            .map(|node| self.entities[node.entity].table.as_str())
+    }
+
+    /// Shows only the selected table and its neighbors, or everything again.
+    pub fn toggle_focus(&mut self) {
+        let entity = self.nodes.get(self.selected).map(|node| node.entity);
+        match (self.focused, entity) {
+            (None, Some(entity)) => {
+                self.focused = Some(entity);
+                self.shown = diagram::neighborhood(&self.entities, entity);
+            }
+            _ => {
+                self.focused = None;
+                self.shown = (0..self.entities.len()).collect();
+            }
+        }
+        self.arrange(entity);
+    }
+
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.offset = (self.offset.0 + dx, self.offset.1 + dy);
+    }
+
+    /// The shown part as DOT for a `.dot` or `.gv` path, and as Mermaid otherwise.
+    pub fn export(&self, path: &str) -> String {
+        if path.ends_with(".dot") || path.ends_with(".gv") {
+            diagram::dot(&self.entities, &self.shown)
+        } else {
+            diagram::mermaid(&self.entities, &self.shown)
+        }
+    }
+
+    pub fn shown(&self) -> usize {
+        self.shown.len()
+    }
+
+    /// Writes `text` from diagram cell `(x, y)`, leaving out what is outside `area`.
+    fn put(&self, buf: &mut Buffer, area: Rect, (x, y): (i32, i32), text: &str, style: Style) {
+        let row = y - self.offset.1;
+        if row < 0 || row >= area.height as i32 {
+            return;
+        }
+        for (idx, c) in text.chars().enumerate() {
+            let column = x + idx as i32 - self.offset.0;
+            if column >= 0 && column < area.width as i32 {
+                let cell = &mut buf[(area.x + column as u16, area.y + row as u16)];
+                cell.reset();
+                cell.set_char(c).set_style(style);
+            }
+        }
+    }
+}
+
+impl Component for DiagramView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match self.focused {
+                Some(entity) => format!(" Neighborhood of {} ", self.entities[entity].table),
+                None => " Schema diagram ".into(),
+            })
+            .title_bottom(
+                Line::from(" [f] Focus  [Shift+←↑↓→] Pan  [Enter] Open  [E] Export ").centered(),
+            )
+            .border_type(BorderType::Rounded)
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.nodes.is_empty() {
+            Line::from(" No tables.").render(inner, buf);
+            return;
+        }
+
+        if self.center {
+            let (x, y) = self.nodes[self.selected].center();
+            self.offset = (x - inner.width as i32 / 2, y - inner.height as i32 / 2);
+            self.center = false;
+        }
+
+        let selected = self.nodes[self.selected].entity;
+        let node = |entity: usize| self.nodes.iter().find(|node| node.entity == entity);
+        let (left, top) = (self.offset.0 as f64, self.offset.1 as f64);
+
+        // From the referencing box to an arrow just outside the referenced one.
+        let mut arrows = Vec::new();
+        let mut lines = Vec::new();
+        for (child, parent, _) in diagram::edges(&self.entities) {
+            let (Some(from), Some(to)) = (node(child), node(parent)) else {
+                continue;
+            };
+            let (start, end, arrow) = if to.y + to.height <= from.y {
+                (
+                    (from.center().0, from.y - 1),
+                    (to.center().0, to.y + to.height),
+                    '▲',
+                )
+            } else if from.y + from.height <= to.y {
+                (
+                    (from.center().0, from.y + from.height),
+                    (to.center().0, to.y - 1),
+                    '▼',
+                )
+            } else if to.x > from.x {
+                (
+                    (from.x + from.width, from.center().1),
+                    (to.x - 1, to.center().1),
+                    '▶',
+                )
+            } else {
+                (
+                    (from.x - 1, from.center().1),
+                    (to.x + to.width, to.center().1),
+                    '◀',
+                )
+            };
+            let color = if child == selected || parent == selected {
+                Color::Yellow
+            } else {
+                Color::Gray
+            };
+            lines.push((start, end, color));
+            arrows.push((end, arrow, color));
+        }
+
+        Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([left, left + inner.width as f64])
+            // Canvas y grows upwards, diagram rows downwards.
+            .y_bounds([-top - inner.height as f64, -top])
+            .paint(|ctx| {
+                for ((x1, y1), (x2, y2), color) in &lines {
+                    ctx.draw(&canvas::Line {
+                        x1: *x1 as f64 + 0.5,
+                        y1: -(*y1 as f64 + 0.5),
+                        x2: *x2 as f64 + 0.5,
+                        y2: -(*y2 as f64 + 0.5),
+                        color: *color,
+                    });
+                }
+            })
+            .render(inner, buf);
+
+        for (at, arrow, color) in arrows {
+            self.put(
+                buf,
+                inner,
+                at,
+                &arrow.to_string(),
+                Style::default().fg(color),
+            );
+        }
+
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let entity = &self.entities[node.entity];
+            let border = if idx == self.selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            let inside = (node.width - 2) as usize;
+            let row = |text: &str| format!(" {:<width$} ", text, width = inside - 2);
+
+            let mut y = node.y;
+            self.put(
+                buf,
+                inner,
+                (node.x, y),
+                &format!("╭{}╮", "─".repeat(inside)),
+                border,
+            );
+            y += 1;
+            self.put(buf, inner, (node.x, y), "│", border);
+            self.put(
+                buf,
+                inner,
+                (node.x + 1, y),
+                &row(&entity.table),
+                Style::default().add_modifier(Modifier::BOLD),
+            );
+            self.put(buf, inner, (node.x + node.width - 1, y), "│", border);
+
+            let lines = entity.key_lines();
+            if !lines.is_empty() {
+                y += 1;
+                self.put(
+                    buf,
+                    inner,
+                    (node.x, y),
+                    &format!("├{}┤", "─".repeat(inside)),
+                    border,
+                );
+            }
+            for line in lines {
+                y += 1;
+                self.put(buf, inner, (node.x, y), "│", border);
+                self.put(buf, inner, (node.x + 1, y), &row(&line), Style::default());
+                self.put(buf, inner, (node.x + node.width - 1, y), "│", border);
+            }
+            y += 1;
+            self.put(
+                buf,
+                inner,
+                (node.x, y),
+                &format!("╰{}╯", "─".repeat(inside)),
+                border,
+            );
+        }
+    }
+
+    fn next(&mut self) {
+        if self.selected + 1 < self.nodes.len() {
+            self.selected += 1;
+            self.center = true;
+        }
+    }
+
+    fn prev(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+            self.center = true;
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{
+    edit::{self, Key, Locate, Value},
+    schema::{self, Column},
+    widgets::TextInput,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, BorderType, Cell, Clear, Padding, Paragraph, Row, StatefulWidget, Table, TableState,
+        Widget,
+    },
+};
+
+pub struct Field {
+    pub column: Column,
+    pub value: Value,
+    pub input: TextInput,
+}
+
+/// A popup listing every column of a table, to fill in a row to insert.
+pub struct InsertForm {
+    pub table: String,
+    pub fields: Vec<Field>,
+    pub error: Option<String>,
+    state: TableState,
+}
+
+impl InsertForm {
+    pub fn new(table: impl Into<String>, columns: &[Column]) -> Self {
+        let fields = columns
+            .iter()
+            .map(|column| Field {
+                value: if column.auto_increment || column.default.is_some() {
+                    Value::Default
+                } else if column.nullable {
+                    Value::Null
+                } else {
+                    Value::Text(String::new())
+                },
+                column: column.clone(),
+                input: TextInput::default(),
+            })
+            .collect();
+
+        Self {
+            table: table.into(),
+            fields,
+            error: None,
+            state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    fn field(&mut self) -> Option<&mut Field> {
+        self.fields.get_mut(self.state.selected()?)
+    }
+
+    pub fn next(&mut self) {
+        if let Some(n) = self.state.selected() {
+            self.state.select(Some((n + 1) % self.fields.len().max(1)));
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if let Some(n) = self.state.selected() {
+            let len = self.fields.len().max(1);
+            self.state.select(Some((n + len - 1) % len));
+        }
+    }
+
+    /// Types into the selected field, which then holds text.
+    pub fn edit(&mut self, key: KeyEvent) {
+        if let Some(field) = self.field()
+            && field.input.edit(key)
+        {
+            field.value = Value::Text(field.input.text.clone());
+        }
+    }
+
+    pub fn set(&mut self, value: Value) {
+        if let Some(field) = self.field() {
+            field.value = value;
+        }
+    }
+
+    /// The values to insert, once every field is valid for its column.
+    pub fn values(&self) -> Result<Vec<Value>, String> {
+        for field in &self.fields {
+            let column = &field.column;
+            match &field.value {
+                Value::Null if !column.nullable && !column.auto_increment => {
+                    return Err(format!("{} cannot be NULL", column.name));
+                }
+                Value::Text(text) => edit::validate(column, text)?,
+                _ => (),
+            }
+        }
+
+        Ok(self
+            .fields
+            .iter()
+            .map(|field| field.value.clone())
+            .collect())
+    }
+
+    /// How to find the row once inserted, from the values given for its key.
+    pub fn locate(&self) -> Locate {
+        let key = schema::primary_key(
+            &self
+                .fields
+                .iter()
+                .map(|field| field.column.clone())
+                .collect::<Vec<Column>>(),
+        );
+        let fields: Vec<&Field> = key
+            .iter()
+            .filter_map(|name| self.fields.iter().find(|field| &field.column.name == name))
+            .collect();
+
+        match fields.as_slice() {
+            [] => Locate::Unknown,
+            [field] if field.column.auto_increment && !matches!(field.value, Value::Text(_)) => {
+                Locate::Generated(field.column.name.clone())
+            }
+            fields => match fields
+                .iter()
+                .map(|field| match &field.value {
+                    Value::Text(text) => Some(Some(text.clone())),
+                    Value::Null => Some(None),
+                    _ => None,
+                })
+                .collect::<Option<Vec<Option<String>>>>()
+            {
+                Some(values) => Locate::Key(Key {
+                    columns: key,
+                    values,
+                }),
+                None => Locate::Unknown,
+            },
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Percentage(70)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(self.fields.len() as u16 + 9)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buf);
+
+        let block = Block::bordered()
+            .title(format!(" Insert into {} ", self.table))
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [table_area, _, error_area, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+        ];
+        let value_width = Layout::horizontal(widths).spacing(1).split(table_area)[3].width;
+        let selected = self.state.selected();
+        let dim = Style::default().fg(Color::DarkGray);
+
+        let rows = self.fields.iter().enumerate().map(|(idx, field)| {
+            let column = &field.column;
+
+            let mut flags = Vec::new();
+            if column.pk > 0 {
+                flags.push("PK".to_string());
+            }
+            if column.auto_increment {
+                flags.push("AUTO".into());
+            }
+            if !column.nullable {
+                flags.push("NOT NULL".into());
+            }
+            if let Some(default) = &column.default {
+                flags.push(format!("= {}", default));
+            }
+
+            let value = match &field.value {
+                Value::Text(_) if selected == Some(idx) => {
+                    field.input.line(value_width.saturating_sub(3) as usize)
+                }
+                Value::Text(text) => Line::from(text.clone()),
+                value => Line::styled(value.shown(), dim),
+            };
+
+            Row::new([
+                Cell::from(column.name.clone()),
+                Cell::from(column.data_type.clone()),
+                Cell::from(flags.join(" ")),
+                Cell::from(value),
+            ])
+        });
+
+        StatefulWidget::render(
+            Table::new(rows, widths)
+                .header(
+                    Row::new(["Column", "Type", "Constraints", "Value"])
+                        .style(Modifier::BOLD)
+                        .bottom_margin(1),
+                )
+                .highlight_symbol(">> ")
+                .row_highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+            table_area,
+            buf,
+            &mut self.state,
+        );
+
+        if let Some(error) = &self.error {
+            Paragraph::new(error.as_str())
+                .style(Style::default().fg(Color::Red))
+                .render(error_area, buf);
+        }
+
+        Paragraph::new(
+            "[↑/↓] Field  [Ctrl+N] NULL  [Ctrl+D] Default  [Enter] Insert  [Esc] Cancel",
+        )
+        .style(dim)
+        .centered()
+        .render(hint_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str) -> Column {
+        Column {
+            name: name.into(),
+            data_type: data_type.into(),
+            nullable: true,
+            default: None,
+            pk: 0,
+            auto_increment: false,
+        }
+    }
+
+    fn columns() -> Vec<Column> {
+        let mut id = column("id", "INTEGER");
+        id.pk = 1;
+        id.nullable = false;
+        id.auto_increment = true;
+        let mut name = column("name", "VARCHAR(8)");
+        name.nullable = false;
+        let mut status = column("status", "TEXT");
+        status.nullable = false;
+        status.default = Some("'new'".into());
+        vec![id, name, status, column("note", "TEXT")]
+    }
+
+    #[test]
+    fn fields_start_from_defaults_and_nullability() {
+        let form = InsertForm::new("t", &columns());
+        let values: Vec<&Value> = form.fields.iter().map(|field| &field.value).collect();
+        assert_eq!(
+            values,
+            [
+                &Value::Default,
+                &Value::Text(String::new()),
+                &Value::Default,
+                &Value::Null,
+            ]
+        );
+    }
+
+    #[test]
+    fn values_refuse_null_only_where_the_column_needs_a_value() {
+        let mut form = InsertForm::new("t", &columns());
+        form.fields[1].value = Value::Text("ada".into());
+        assert!(form.values().is_ok());
+
+        // An auto-increment column takes NULL as asking for the next id.
+        form.fields[0].value = Value::Null;
+        assert!(form.values().is_ok());
+
+        form.fields[1].value = Value::Null;
+        assert_eq!(form.values(), Err("name cannot be NULL".into()));
+    }
+
+    #[test]
+    fn values_are_checked_against_their_type() {
+        let mut form = InsertForm::new("t", &columns());
+        form.fields[1].value = Value::Text("far too long".into());
+        assert_eq!(
+            form.values(),
+            Err("name expects at most 8 characters".into())
+        );
+
+        form.fields[1].value = Value::Text("ada".into());
+        form.fields[0].value = Value::Text("x".into());
+        assert_eq!(form.values(), Err("id expects an integer".into()));
+    }
+
+    #[test]
+    fn locate_finds_the_row_by_its_key_or_generated_id() {
+        let mut form = InsertForm::new("t", &columns());
+        assert!(matches!(form.locate(), Locate::Generated(column) if column == "id"));
+
+        form.fields[0].value = Value::Text("42".into());
+        assert!(matches!(
+            form.locate(),
+            Locate::Key(key) if key.columns == ["id"] && key.values == [Some("42".to_string())]
+        ));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    schema::{ForeignKey, Index},
+    widgets::Component,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, BorderType, Cell, Padding, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+    },
+};
+
+/// The indexes and foreign keys of a table, next to its data grid.
+#[derive(Default)]
+pub struct KeysView {
+    pub table: Option<String>,
+    pub indexes: Vec<Index>,
+    pub foreign_keys: Vec<ForeignKey>,
+    /// Selected row, over the indexes and then the foreign keys.
+    pub selected: Option<usize>,
+    index_state: TableState,
+    key_state: TableState,
+}
+
+impl KeysView {
+    pub fn new(table: String, indexes: Vec<Index>, foreign_keys: Vec<ForeignKey>) -> Self {
+        Self {
+            table: Some(table),
+            indexes,
+            foreign_keys,
+            ..Default::default()
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.indexes.len() + self.foreign_keys.len()
+    }
+}
+
+impl Component for KeysView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match &self.table {
+                Some(table) => format!(" Indexes and foreign keys of {} ", table),
+                None => " Indexes and foreign keys ".into(),
+            })
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [index_title, index_area, _, key_title, key_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(self.indexes.len().max(1) as u16 + 2),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let dim = Style::default().fg(Color::DarkGray);
+
+        self.index_state
+            .select(self.selected.filter(|&n| n < self.indexes.len()));
+        self.key_state.select(
+            self.selected
+                .and_then(|n| n.checked_sub(self.indexes.len())),
+        );
+
+        Paragraph::new(Line::styled("Indexes", bold.fg(Color::Cyan))).render(index_title, buf);
+        StatefulWidget::render(
+            Table::new(
+                self.indexes.iter().map(|index| {
+                    Row::new([
+                        Cell::from(index.name.clone()),
+                        Cell::from(index.columns.join(", ")),
+                        Cell::from(if index.primary {
+                            "PRIMARY"
+                        } else if index.unique {
+                            "UNIQUE"
+                        } else {
+                            ""
+                        }),
+                        match &index.partial {
+                            Some(partial) => Cell::from(format!("WHERE {}", partial)),
+                            None => Cell::from("—").style(dim),
+                        },
+                    ])
+                }),
+                [
+                    Constraint::Fill(2),
+                    Constraint::Fill(3),
+                    Constraint::Length(8),
+                    Constraint::Fill(3),
+                ],
+            )
+            .header(Row::new(["Name", "Columns", "Kind", "Partial"]).style(bold))
+            .highlight_symbol(">> ")
+            .row_highlight_style(bold),
+            index_area,
+            buf,
+            &mut self.index_state,
+        );
+
+        Paragraph::new(Line::styled("Foreign keys", bold.fg(Color::Cyan))).render(key_title, buf);
+        StatefulWidget::render(
+            Table::new(
+                self.foreign_keys.iter().map(|key| {
+                    Row::new([
+                        Cell::from(key.name.clone()),
+                        Cell::from(format!(
+                            "({}) → {} ({})",
+                            key.columns.join(", "),
+                            key.table,
+                            key.references.join(", ")
+                        )),
+                        Cell::from(key.on_delete.clone()),
+                        Cell::from(key.on_update.clone()),
+                    ])
+                }),
+                [
+                    Constraint::Fill(2),
+                    Constraint::Fill(5),
+                    Constraint::Fill(1),
+                    Constraint::Fill(1),
+                ],
+            )
+            .header(Row::new(["Name", "Reference", "On delete", "On update"]).style(bold))
+            .highlight_symbol(">> ")
+            .row_highlight_style(bold),
+            key_area,
+            buf,
+            &mut self.key_state,
+        );
+    }
+
+    fn next(&mut self) {
+        match self.selected {
+            Some(n) if n + 1 < self.len() => self.selected = Some(n + 1),
+            None if self.len() > 0 => self.selected = Some(0),
+            _ => (),
+        }
+    }
+
+    fn prev(&mut self) {
+        match self.selected {
+            Some(n) if n > 0 => self.selected = Some(n - 1),
+            None if self.len() > 0 => self.selected = Some(0),
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Clear, List, ListState, Padding, StatefulWidget, Widget},
+};
+
+/// A centered list of choices drawn on top of the other widgets.
+pub struct Menu {
+    pub title: String,
+    pub items: Vec<String>,
+    pub state: ListState,
+}
+
+impl Menu {
+    pub fn new(title: impl Into<String>, items: Vec<String>) -> Self {
+        Self {
+            title: title.into(),
+            items,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn next(&mut self) {
+        if let Some(n) = self.state.selected() {
+            self.state.select(Some((n + 1) % self.items.len().max(1)));
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if let Some(n) = self.state.selected() {
+            let len = self.items.len().max(1);
+            self.state.select(Some((n + len - 1) % len));
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Length(40)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(self.items.len() as u16 + 6)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buf);
+
+        let block = Block::bordered()
+            .title(format!(" {} ", self.title))
+            .title_bottom(Line::from(" [Enter] Choose  [Esc] Cancel ").centered())
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+
+        StatefulWidget::render(
+            List::new(self.items.iter().map(|item| item.as_str()))
+                .block(block)
+                .highlight_symbol(">> ")
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+            area,
+            buf,
+            &mut self.state,
+        );
+    }
+}
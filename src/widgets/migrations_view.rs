@@ -0,0 +1,193 @@
+use crate::{
+    migrations::{History, Migration},
+    widgets::Component,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{
+        Block, BorderType, Cell, Padding, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+    },
+};
+
+/// Checksums are cut to this many characters.
+const CHECKSUM: usize = 16;
+
+/// The migrations recorded by the migration tools found in the database, as a
+/// timeline, oldest first.
+#[derive(Default)]
+pub struct MigrationsView {
+    pub histories: Vec<History>,
+    pub state: TableState,
+}
+
+impl MigrationsView {
+    pub fn new(histories: Vec<History>) -> Self {
+        // The latest migration, at the bottom, is the one asked about most.
+        let rows = histories
+            .iter()
+            .map(|history| history.migrations.len() + 1)
+            .sum::<usize>();
+        Self {
+            histories,
+            state: TableState::default().with_selected(rows.checked_sub(1)),
+        }
+    }
+
+    /// How many migrations there are, and how many failed or were left dirty.
+    pub fn counts(&self) -> (usize, usize) {
+        self.histories
+            .iter()
+            .flat_map(|history| &history.migrations)
+            .fold((0, 0), |(all, broken), migration| {
+                (all + 1, broken + migration.is_broken() as usize)
+            })
+    }
+
+    fn rows(&self) -> usize {
+        self.histories
+            .iter()
+            .map(|history| history.migrations.len() + 1)
+            .sum()
+    }
+}
+
+fn status(migration: &Migration) -> (&'static str, &'static str, Style) {
+    if migration.dirty {
+        ("◐", "dirty", Style::default().fg(Color::Yellow))
+    } else if migration.success == Some(false) {
+        ("✗", "failed", Style::default().fg(Color::Red))
+    } else if migration.success == Some(true) {
+        ("●", "applied", Style::default().fg(Color::Green))
+    } else {
+        ("●", "recorded", Style::default())
+    }
+}
+
+impl Component for MigrationsView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let (all, broken) = self.counts();
+        let block = Block::bordered()
+            .title(match broken {
+                0 => format!(" Migrations: {} ", all),
+                _ => format!(" Migrations: {}, {} failed or dirty ", all, broken),
+            })
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        if self.histories.is_empty() {
+            Paragraph::new("No migration table found (_sqlx_migrations, __diesel_schema_migrations, refinery_schema_history, schema_migrations).")
+                .block(block)
+                .centered()
+                .render(area, buf);
+            return;
+        }
+
+        let dim = Style::default().fg(Color::DarkGray);
+        let heading = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        let optional = |value: &Option<String>| match value {
+            Some(value) => Cell::from(value.clone()),
+            None => Cell::from("—").style(dim),
+        };
+
+        let mut rows = Vec::new();
+        for history in &self.histories {
+            rows.push(
+                Row::new([
+                    String::new(),
+                    history.tool.label().into(),
+                    history.table.clone(),
+                    format!("{} migration(s)", history.migrations.len()),
+                ])
+                .style(heading),
+            );
+            for (idx, migration) in history.migrations.iter().enumerate() {
+                let (glyph, label, style) = status(migration);
+                let checksum = migration.checksum.as_ref().map(|checksum| {
+                    match checksum.char_indices().nth(CHECKSUM) {
+                        Some((end, _)) => format!("{}…", &checksum[..end]),
+                        None => checksum.clone(),
+                    }
+                });
+                // A line runs down the timeline between entries.
+                let glyph = if idx + 1 < history.migrations.len() {
+                    format!("{}\n│", glyph)
+                } else {
+                    glyph.into()
+                };
+
+                let row = Row::new([
+                    Cell::from(glyph).style(style),
+                    Cell::from(migration.version.clone()),
+                    optional(&migration.description),
+                    optional(&migration.applied),
+                    optional(&checksum),
+                    Cell::from(label).style(style),
+                ])
+                .height(2);
+                rows.push(if migration.is_broken() {
+                    row.style(Style::default().add_modifier(Modifier::BOLD))
+                } else {
+                    row
+                });
+            }
+        }
+
+        StatefulWidget::render(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(1),
+                    Constraint::Fill(2),
+                    Constraint::Fill(3),
+                    Constraint::Fill(2),
+                    Constraint::Length(CHECKSUM as u16 + 1),
+                    Constraint::Length(8),
+                ],
+            )
+            .block(block)
+            .header(
+                Row::new([
+                    "",
+                    "Version",
+                    "Description",
+                    "Applied",
+                    "Checksum",
+                    "Status",
+                ])
+                .style(Modifier::BOLD)
+                .bottom_margin(1),
+            )
+            .highlight_symbol(">> ")
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            area,
+            buf,
+            &mut self.state,
+        );
+    }
+
+    fn next(&mut self) {
+        match self.state.selected() {
+            Some(n) if n + 1 < self.rows() => self.state.select(Some(n + 1)),
+            None if self.rows() > 0 => self.state.select(Some(0)),
+            _ => (),
+        }
+    }
+
+    fn prev(&mut self) {
+        match self.state.selected() {
+            Some(n) if n > 0 => self.state.select(Some(n - 1)),
+            _ => (),
+        }
+    }
+}
@@ -1,11 +1,47 @@
+mod changes_view;
+mod compare_view;
+mod confirm;
+mod data_diff_view;
+mod ddl_view;
+mod dependents_view;
+mod diagram_view;
+mod insert_form;
+mod keys_view;
+mod menu;
+mod migrations_view;
+mod object_view;
+mod plan_view;
+mod prompt;
+mod script_view;
+mod status_bar;
+mod structure_view;
 mod table_list;
 mod table_page;
 mod table_view;
+mod text_input;
 
+pub use changes_view::ChangesView;
+pub use compare_view::CompareView;
+pub use confirm::Confirm;
+pub use data_diff_view::DataDiffView;
+pub use ddl_view::DdlView;
+pub use dependents_view::DependentsView;
+pub use diagram_view::DiagramView;
+pub use insert_form::InsertForm;
+pub use keys_view::KeysView;
+pub use menu::Menu;
+pub use migrations_view::MigrationsView;
+pub use object_view::ObjectView;
+pub use plan_view::PlanView;
+pub use prompt::Prompt;
 use ratatui::{buffer::Buffer, layout::Rect};
+pub use script_view::ScriptView;
+pub use status_bar::{Message, StatusBar};
+pub use structure_view::StructureView;
 pub use table_list::TableList;
 pub use table_page::TablePage;
 pub use table_view::TableView;
+pub use text_input::TextInput;
 
 pub trait Component {
     fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool);
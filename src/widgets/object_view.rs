@@ -0,0 +1,96 @@
+use crate::{
+    schema::{Object, ObjectKind},
+    widgets::{Component, ddl_view::highlight},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Padding, Paragraph, Widget, Wrap},
+};
+
+/// The trigger, routine or event selected in the table list.
+#[derive(Default)]
+pub struct ObjectView {
+    pub object: Option<Object>,
+    /// First line shown.
+    pub scroll: u16,
+}
+
+impl Component for ObjectView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match &self.object {
+                Some(object) => format!(" {} {} ", object.kind.label(), object.name),
+                None => " Object ".into(),
+            })
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let Some(object) = &self.object else {
+            Paragraph::new("Select a trigger, routine or event.")
+                .block(block)
+                .render(area, buf);
+            return;
+        };
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let dim = Style::default().fg(Color::DarkGray);
+        let field = |name: &'static str, value: Option<&str>| {
+            Line::from(vec![
+                Span::styled(format!("{:<9}", name), bold),
+                match value {
+                    Some(value) if !value.is_empty() => Span::raw(value.to_string()),
+                    _ => Span::styled("—", dim),
+                },
+            ])
+        };
+
+        let mut lines = Vec::new();
+        if let Some(table) = &object.table {
+            lines.push(field("Table", Some(table)));
+        }
+        match object.kind {
+            ObjectKind::Procedure => (),
+            ObjectKind::Function => lines.push(field("Returns", Some(&object.timing))),
+            _ => lines.push(field("Runs", Some(&object.timing))),
+        }
+        lines.push(field("Definer", object.definer.as_deref()));
+        lines.push(Line::default());
+        if object.definition.is_empty() {
+            lines.push(Line::styled(
+                "The definition is hidden without the privileges to see it.",
+                dim,
+            ));
+        }
+        lines.extend(object.definition.lines().map(highlight));
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+
+    fn next(&mut self) {
+        let lines = self
+            .object
+            .as_ref()
+            .map_or(0, |object| object.definition.lines().count() + 4);
+        if (self.scroll as usize) + 1 < lines {
+            self.scroll += 1;
+        }
+    }
+
+    fn prev(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{plan::Plan, widgets::Component};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, BorderType, List, ListState, Padding, Paragraph, StatefulWidget, Widget, Wrap,
+    },
+};
+
+#[derive(Default)]
+pub struct PlanView {
+    pub plan: Plan,
+    pub state: ListState,
+}
+
+impl Component for PlanView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(" Plan ")
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [info_area, _, tree_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        let mut info = vec![Line::from(self.plan.sql.replace(['\n', '\r'], " "))];
+        if let Some(cost) = self.plan.cost {
+            info.push(Line::from(format!("Query cost: {}", cost)).style(Modifier::BOLD));
+        }
+
+        Paragraph::new(info)
+            .wrap(Wrap { trim: true })
+            .render(info_area, buf);
+
+        StatefulWidget::render(
+            List::new(self.plan.nodes.iter().map(|node| {
+                let line = Line::from(format!(
+                    "{}{} {}",
+                    "   ".repeat(node.depth),
+                    if node.depth == 0 { "●" } else { "└─" },
+                    node.detail
+                ));
+
+                if node.full_scan {
+                    line.style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+                } else {
+                    line
+                }
+            }))
+            .highlight_symbol(">> ")
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+            tree_area,
+            buf,
+            &mut self.state,
+        );
+    }
+
+    fn next(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n + 1 < self.plan.nodes.len()
+        {
+            self.state.select(Some(n + 1));
+        } else if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn prev(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n > 0
+        {
+            self.state.select(Some(n - 1));
+        } else {
+            self.state.select(Some(0));
+        }
+    }
+}
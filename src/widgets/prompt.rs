@@ -0,0 +1,55 @@
+use crate::widgets::TextInput;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Paragraph, Widget},
+};
+
+/// A centered single-line text input drawn on top of the other widgets.
+pub struct Prompt {
+    pub title: String,
+    pub hint: String,
+    pub input: TextInput,
+}
+
+impl Prompt {
+    pub fn new(title: impl Into<String>, text: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            hint: hint.into(),
+            input: TextInput::new(text),
+        }
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Percentage(50)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(7)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buf);
+
+        let block = Block::bordered()
+            .title(format!(" {} ", self.title))
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let width = block.inner(area).width as usize;
+
+        Paragraph::new(vec![
+            self.input.line(width),
+            Line::default(),
+            Line::styled(self.hint.as_str(), Style::default().fg(Color::DarkGray)).centered(),
+        ])
+        .block(block)
+        .render(area, buf);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    db::{self, Fetched, Outcome, ResultSet, Values},
+    script::{StatementResult, Status},
+    widgets::Component,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, BorderType, Padding, Paragraph, Row, StatefulWidget, Table, TableState, Tabs,
+        Widget, Wrap,
+    },
+};
+
+#[derive(Default)]
+pub struct ScriptView {
+    pub results: Vec<StatementResult>,
+    pub selected: usize,
+    pub state: TableState,
+}
+
+impl ScriptView {
+    /// The result set still backed by an open cursor, always the last statement's.
+    fn open_set(&mut self) -> Option<&mut ResultSet> {
+        match self.results.last_mut() {
+            Some(StatementResult {
+                status: Status::Done(Outcome::Rows(set)),
+                ..
+            }) if set.fetched == Fetched::More => Some(set),
+            _ => None,
+        }
+    }
+
+    pub fn append(&mut self, rows: Vec<Values>, fetched: Fetched) {
+        if let Some(set) = self.open_set() {
+            rows.into_iter().for_each(|row| set.push(row));
+            set.fetched = fetched;
+        }
+    }
+
+    pub fn close(&mut self) {
+        if let Some(set) = self.open_set() {
+            set.fetched = Fetched::Truncated;
+        }
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        matches!(
+            self.results.last(),
+            Some(StatementResult {
+                status: Status::Done(Outcome::Rows(set)),
+                ..
+            }) if set.fetched == Fetched::More
+        )
+    }
+
+    /// Whether the open result set is shown with its selection within `margin` rows
+    /// of the loaded end.
+    pub fn wants_more(&self, margin: usize) -> bool {
+        self.is_fetching()
+            && self.selected + 1 == self.results.len()
+            && self
+                .results
+                .last()
+                .is_some_and(|result| match &result.status {
+                    Status::Done(Outcome::Rows(set)) => {
+                        self.state.selected().unwrap_or(0).saturating_add(margin) >= set.rows.len()
+                    }
+                    _ => false,
+                })
+    }
+
+    pub fn next_tab(&mut self) {
+        if !self.results.is_empty() {
+            self.selected = (self.selected + 1) % self.results.len();
+            self.state = TableState::default();
+        }
+    }
+
+    pub fn prev_tab(&mut self) {
+        if !self.results.is_empty() {
+            self.selected = (self.selected + self.results.len() - 1) % self.results.len();
+            self.state = TableState::default();
+        }
+    }
+}
+
+impl Component for ScriptView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(" Script ")
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [tabs_area, info_area, _, result_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        Tabs::new(self.results.iter().enumerate().map(|(idx, result)| {
+            let (mark, color) = match result.status {
+                Status::Done(_) => ("✔", Color::Green),
+                Status::Failed(_) => ("✘", Color::Red),
+                Status::Skipped => ("-", Color::DarkGray),
+            };
+            Line::styled(format!("{} {}", idx + 1, mark), Style::default().fg(color))
+        }))
+        .select(self.selected)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .render(tabs_area, buf);
+
+        let Some(result) = self.results.get(self.selected) else {
+            return;
+        };
+
+        let summary = match &result.status {
+            Status::Done(Outcome::Rows(set)) => match set.fetched {
+                Fetched::Complete => format!("{} row(s) returned", set.rows.len()),
+                Fetched::More => {
+                    format!("{} row(s) loaded so far, still fetching…", set.rows.len())
+                }
+                Fetched::Truncated => {
+                    format!(
+                        "First {} row(s) loaded, the rest was discarded",
+                        set.rows.len()
+                    )
+                }
+                Fetched::Capped => {
+                    format!(
+                        "{} row(s) loaded, stopped at the memory cap",
+                        set.rows.len()
+                    )
+                }
+            },
+            Status::Done(Outcome::Affected(n)) => format!("{} row(s) affected", n),
+            Status::Failed(err) => format!("Error: {}", err),
+            Status::Skipped => "Skipped after an earlier error".into(),
+        };
+
+        Paragraph::new(vec![
+            Line::from(result.sql.replace(['\n', '\r'], " ")),
+            Line::from(format!("{} ({:.2?})", summary, result.duration)),
+        ])
+        .wrap(Wrap { trim: true })
+        .render(info_area, buf);
+
+        if let Status::Done(Outcome::Rows(set)) = &result.status {
+            StatefulWidget::render(
+                Table::new(
+                    set.rows
+                        .iter()
+                        .map(|row| Row::new(row.iter().map(db::shown))),
+                    (0..set.columns.len()).map(|_| Constraint::Fill(1)),
+                )
+                .header(
+                    Row::new(set.columns.iter().map(|key| key.as_str()))
+                        .style(Modifier::BOLD)
+                        .bottom_margin(1),
+                )
+                .highlight_symbol(">> ")
+                .row_highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+                result_area,
+                buf,
+                &mut self.state,
+            );
+        }
+    }
+
+    fn next(&mut self) {
+        if let Some(StatementResult {
+            status: Status::Done(Outcome::Rows(set)),
+            ..
+        }) = self.results.get(self.selected)
+            && let Some(n) = self.state.selected()
+            && n + 1 < set.rows.len()
+        {
+            self.state.select(Some(n + 1));
+        } else if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn prev(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n > 0
+        {
+            self.state.select(Some(n - 1));
+        } else {
+            self.state.select(Some(0));
+        }
+    }
+}
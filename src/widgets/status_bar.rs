@@ -0,0 +1,92 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Widget,
+};
+use std::time::Duration;
+
+pub enum Message {
+    Info(String),
+    Error(String),
+}
+
+#[derive(Default)]
+pub struct StatusBar {
+    pub message: Option<Message>,
+    pub running: Option<(&'static str, Duration)>,
+    /// Rows done out of the total, for a running job that reports them.
+    pub progress: Option<(u64, u64)>,
+    pub transaction: Option<Duration>,
+    pub read_only: bool,
+}
+
+impl StatusBar {
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let mut spans = Vec::new();
+
+        if self.read_only {
+            spans.push(Span::styled(
+                " READ-ONLY ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(" "));
+        }
+
+        if let Some(elapsed) = self.transaction {
+            let secs = elapsed.as_secs();
+            spans.push(Span::styled(
+                format!(" IN TRANSACTION {:02}:{:02} ", secs / 60, secs % 60),
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(" "));
+        }
+
+        if let Some((label, elapsed)) = self.running {
+            spans.push(Span::styled(
+                format!(" {}… {:.1}s ", label, elapsed.as_secs_f64()),
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            if let Some((done, total)) = self.progress {
+                let ratio = if total == 0 {
+                    1.0
+                } else {
+                    (done as f64 / total as f64).min(1.0)
+                };
+                let filled = (ratio * 20.0).round() as usize;
+                spans.push(Span::styled(
+                    format!(" {}{}", "█".repeat(filled), "░".repeat(20 - filled)),
+                    Style::default().fg(Color::Cyan),
+                ));
+                spans.push(Span::raw(format!(
+                    " {:.0}% {}/{} rows ",
+                    ratio * 100.0,
+                    done,
+                    total
+                )));
+            }
+            spans.push(Span::raw(" Ctrl+C to cancel "));
+        }
+
+        match &self.message {
+            Some(Message::Info(text)) => spans.push(Span::raw(format!(" {}", text))),
+            Some(Message::Error(text)) => spans.push(Span::styled(
+                format!(" {}", text),
+                Style::default().fg(Color::Red),
+            )),
+            None => (),
+        }
+
+        Line::from(spans).render(area, buf);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{schema::Detail, widgets::Component};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Cell, Padding, Row, StatefulWidget, Table, TableState},
+};
+
+/// The declared columns of a table, next to its data grid.
+#[derive(Default)]
+pub struct StructureView {
+    pub table: Option<String>,
+    pub details: Vec<Detail>,
+    pub state: TableState,
+}
+
+impl Component for StructureView {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let block = Block::bordered()
+            .title(match &self.table {
+                Some(table) => format!(" Structure of {} ", table),
+                None => " Structure ".into(),
+            })
+            .border_type(BorderType::Rounded)
+            .padding(Padding::uniform(1))
+            .border_style(if focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        let dim = Style::default().fg(Color::DarkGray);
+        let rows = self.details.iter().map(|detail| {
+            let column = &detail.column;
+            let optional = |value: &Option<String>| match value {
+                Some(value) => Cell::from(value.clone()),
+                None => Cell::from("—").style(dim),
+            };
+
+            Row::new([
+                Cell::from(column.name.clone()),
+                Cell::from(column.data_type.clone()),
+                Cell::from(if column.nullable { "YES" } else { "NO" }),
+                optional(&column.default),
+                Cell::from(if column.pk > 0 {
+                    column.pk.to_string()
+                } else {
+                    String::new()
+                }),
+                Cell::from(if column.auto_increment { "YES" } else { "" }),
+                optional(&detail.collation),
+                optional(&detail.comment),
+                optional(&detail.extra),
+            ])
+            .style(if column.pk > 0 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            })
+        });
+
+        StatefulWidget::render(
+            Table::new(
+                rows,
+                [
+                    Constraint::Fill(2),
+                    Constraint::Fill(2),
+                    Constraint::Length(8),
+                    Constraint::Fill(2),
+                    Constraint::Length(3),
+                    Constraint::Length(4),
+                    Constraint::Fill(2),
+                    Constraint::Fill(3),
+                    Constraint::Fill(2),
+                ],
+            )
+            .header(
+                Row::new([
+                    "Column",
+                    "Type",
+                    "Nullable",
+                    "Default",
+                    "PK",
+                    "Auto",
+                    "Collation",
+                    "Comment",
+                    "Extra",
+                ])
+                .style(Modifier::BOLD)
+                .bottom_margin(1),
+            )
+            .block(block)
+            .highlight_symbol(">> ")
+            .row_highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+            area,
+            buf,
+            &mut self.state,
+        );
+    }
+
+    fn next(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n + 1 < self.details.len()
+        {
+            self.state.select(Some(n + 1));
+        } else if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn prev(&mut self) {
+        if let Some(n) = self.state.selected()
+            && n > 0
+        {
+            self.state.select(Some(n - 1));
+        } else {
+            self.state.select(Some(0));
+        }
+    }
+}
@@ -1,21 +1,141 @@
-use crate::widgets::Component;
+use crate::{
+    schema::{Object, ObjectKind, TableStats},
+    widgets::Component,
+};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, BorderType, List, ListState, Padding, Paragraph, StatefulWidget, Widget},
 };
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Order of the tables in the list.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Sort {
+    #[default]
+    Name,
+    /// Most rows first.
+    Rows,
+    /// Largest first.
+    Size,
+}
+
+impl Sort {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Name => Self::Rows,
+            Self::Rows => Self::Size,
+            Self::Size => Self::Name,
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct TableList {
+    /// Tables in the order of the database, which selections refer to.
     pub items: Vec<String>,
+    /// Row counts and sizes, by table.
+    pub stats: HashMap<String, TableStats>,
+    pub sort: Sort,
+    /// Selected table; none while an object is selected.
     pub state: ListState,
+    /// Triggers, routines and events, in groups after the tables.
+    pub objects: Vec<Object>,
+    /// Selected object.
+    pub object: Option<usize>,
+}
+
+impl TableList {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.objects.is_empty()
+    }
+
+    pub fn selected_object(&self) -> Option<&Object> {
+        self.object.and_then(|object| self.objects.get(object))
+    }
+
+    /// Selects the table at `idx`.
+    pub fn select(&mut self, idx: usize) {
+        self.object = None;
+        self.state.select(Some(idx));
+    }
+
+    /// Indexes of the tables, in the order shown.
+    fn order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        let stats = |idx: &usize| {
+            self.stats
+                .get(&self.items[*idx])
+                .copied()
+                .unwrap_or_default()
+        };
+        // Tables without figures go last.
+        match self.sort {
+            Sort::Name => (),
+            Sort::Rows => order.sort_by_key(|idx| Reverse(stats(idx).rows)),
+            Sort::Size => order.sort_by_key(|idx| Reverse(stats(idx).bytes)),
+        }
+        order
+    }
+
+    /// Position over the tables and then the objects.
+    fn position(&self) -> Option<usize> {
+        match self.state.selected() {
+            Some(selected) => self.order().iter().position(|&idx| idx == selected),
+            None => self.object.map(|object| self.items.len() + object),
+        }
+    }
+
+    fn set_position(&mut self, position: usize) {
+        if position < self.items.len() {
+            self.select(self.order()[position]);
+        } else {
+            self.state.select(None);
+            self.object = Some(position - self.items.len());
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.items.len() + self.objects.len()
+    }
+}
+
+/// A row count in short form, such as `12.3k`.
+fn count(rows: u64) -> String {
+    match rows {
+        0..1_000 => rows.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", rows as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1}M", rows as f64 / 1e6),
+        _ => format!("{:.1}G", rows as f64 / 1e9),
+    }
+}
+
+/// A size in bytes in short form, such as `4.0K`.
+fn size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
 }
 
 impl Component for TableList {
     fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
         let block = Block::bordered()
-            .title(" Tables ")
+            .title(match self.sort {
+                Sort::Name => " Tables ",
+                Sort::Rows => " Tables (by rows) ",
+                Sort::Size => " Tables (by size) ",
+            })
             .border_type(BorderType::Rounded)
             .padding(Padding::uniform(1))
             .border_style(if focus {
@@ -26,47 +146,95 @@ impl Component for TableList {
                 Style::default()
             });
 
-        if self.items.is_empty() {
+        let inner = block.inner(area);
+
+        if self.is_empty() {
             Widget::render(
                 Paragraph::new("No such table.").centered().block(block),
                 area,
                 buf,
             );
         } else {
+            // Room left of the highlight symbol for the name and its figures.
+            let width = inner.width.saturating_sub(3) as usize;
+            let dim = Style::default().fg(Color::DarkGray);
+            let order = self.order();
+            let mut lines: Vec<Line> = order
+                .iter()
+                .map(|&idx| {
+                    let item = &self.items[idx];
+                    let Some(stats) = self.stats.get(item) else {
+                        return Line::from(item.as_str());
+                    };
+                    let figures = format!(
+                        "{} {}",
+                        stats.rows.map_or("—".into(), count),
+                        stats.bytes.map_or("—".into(), size)
+                    );
+                    let gap = width.saturating_sub(item.chars().count() + figures.chars().count());
+                    if gap == 0 {
+                        return Line::from(item.as_str());
+                    }
+                    Line::from(vec![
+                        Span::raw(item.as_str()),
+                        Span::styled(format!("{}{}", " ".repeat(gap), figures), dim),
+                    ])
+                })
+                .collect();
+            let mut highlighted = self
+                .state
+                .selected()
+                .and_then(|selected| order.iter().position(|&idx| idx == selected));
+
+            let group = Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD);
+            for kind in ObjectKind::ALL {
+                let mut members = self
+                    .objects
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, object)| object.kind == kind)
+                    .peekable();
+                if members.peek().is_none() {
+                    continue;
+                }
+
+                lines.push(Line::styled(format!("{}s", kind.label()), group));
+                for (idx, object) in members {
+                    if self.object == Some(idx) {
+                        highlighted = Some(lines.len());
+                    }
+                    lines.push(Line::from(format!("  {}", object.name)));
+                }
+            }
+
+            // The headings shift rows, so the list is highlighted by row.
+            let mut state = self.state.clone().with_selected(highlighted);
             StatefulWidget::render(
-                List::new(
-                    self.items
-                        .iter()
-                        .map(|item| item.as_str())
-                        .collect::<Vec<&str>>(),
-                )
-                .block(block)
-                .highlight_symbol(">> ")
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+                List::new(lines)
+                    .block(block)
+                    .highlight_symbol(">> ")
+                    .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
                 area,
                 buf,
-                &mut self.state,
+                &mut state,
             );
+            *self.state.offset_mut() = state.offset();
         }
     }
 
     fn next(&mut self) {
-        if let Some(n) = self.state.selected()
-            && (n + 1) < self.items.len()
-        {
-            self.state.select(Some(n + 1));
-        } else {
-            self.state.select(Some(0));
+        match self.position() {
+            Some(n) if n + 1 < self.len() => self.set_position(n + 1),
+            _ => self.set_position(0),
         }
     }
 
     fn prev(&mut self) {
-        if let Some(n) = self.state.selected()
-            && n > 0
-        {
-            self.state.select(Some(n - 1));
-        } else {
-            self.state.select(Some(self.items.len() - 1));
+        match self.position() {
+            Some(n) if n > 0 => self.set_position(n - 1),
+            _ => self.set_position(self.len() - 1),
         }
     }
 }
@@ -1,23 +1,153 @@
-use crate::widgets::Component;
+use crate::{
+    db::{self, Values},
+    edit::Key,
+    schema::{self, Column},
+    widgets::Component,
+};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Rect},
     style::{Color, Modifier, Style},
     widgets::{
-        Block, BorderType, Padding, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+        Block, BorderType, Cell, Padding, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
     },
 };
 
 #[derive(Default)]
 pub struct TableView {
-    pub items: Option<(Vec<String>, Vec<Vec<String>>)>,
+    pub items: Option<(Vec<String>, Vec<Values>)>,
     pub state: TableState,
+    pub table: Option<String>,
+    pub columns: Vec<Column>,
+    pub rowids: Option<Vec<Option<String>>>,
+    /// Keys of the rows marked for deletion, kept across pages.
+    pub marked: Vec<Key>,
+    /// Staged values of cells, by row key and column, latest last.
+    pub edits: Vec<(Key, String, String)>,
+    /// Keys of rows staged for deletion.
+    pub deletions: Vec<Key>,
+    pub filter: Option<String>,
+}
+
+impl TableView {
+    /// Row and column under the cell cursor.
+    pub fn cell(&self) -> Option<(usize, usize)> {
+        match (&self.items, self.state.selected_cell()) {
+            (Some((_, rows)), Some((row, column))) if row < rows.len() => Some((row, column)),
+            _ => None,
+        }
+    }
+
+    pub fn next_column(&mut self) {
+        if let Some((columns, _)) = &self.items
+            && !columns.is_empty()
+        {
+            let column = self
+                .state
+                .selected_column()
+                .map_or(0, |n| (n + 1) % columns.len());
+            self.state.select_column(Some(column));
+        }
+    }
+
+    pub fn prev_column(&mut self) {
+        if let Some((columns, _)) = &self.items
+            && !columns.is_empty()
+        {
+            let column = self
+                .state
+                .selected_column()
+                .map_or(0, |n| (n + columns.len() - 1) % columns.len());
+            self.state.select_column(Some(column));
+        }
+    }
+
+    /// The key identifying `row`: its primary key, or the rowid on SQLite.
+    pub fn key(&self, row: usize) -> Result<Key, &'static str> {
+        let Some((names, rows)) = &self.items else {
+            return Err("No table selected.");
+        };
+        if row >= rows.len() {
+            return Err("No row selected.");
+        }
+
+        let key = match &self.rowids {
+            Some(rowids) => Key {
+                columns: vec!["rowid".into()],
+                values: vec![rowids[row].clone()],
+            },
+            None => {
+                let columns = schema::primary_key(&self.columns);
+                if columns.is_empty() {
+                    return Err("This table has no primary key to identify rows by.");
+                }
+
+                let values = columns
+                    .iter()
+                    .map(|column| {
+                        names
+                            .iter()
+                            .position(|name| name == column)
+                            .and_then(|idx| rows[row][idx].clone())
+                    })
+                    .collect();
+
+                Key { columns, values }
+            }
+        };
+
+        if key
+            .values
+            .iter()
+            .any(|value| matches!(value.as_deref(), None | Some("<err>" | "<unsupported>")))
+        {
+            return Err("The key of this row cannot be read back.");
+        }
+
+        Ok(key)
+    }
+
+    /// Marks or unmarks the selected row.
+    pub fn toggle_mark(&mut self) -> Result<(), &'static str> {
+        let Some(row) = self.state.selected() else {
+            return Ok(());
+        };
+        let key = self.key(row)?;
+
+        match self.marked.iter().position(|marked| *marked == key) {
+            Some(idx) => {
+                self.marked.remove(idx);
+            }
+            None => self.marked.push(key),
+        }
+
+        Ok(())
+    }
+
+    fn row_style(&self, key: Option<&Key>) -> Style {
+        match key {
+            Some(key) if self.deletions.contains(key) => Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT),
+            Some(key) if self.marked.contains(key) => {
+                Style::default().fg(Color::Black).bg(Color::Red)
+            }
+            _ => Style::default(),
+        }
+    }
 }
 
 impl Component for TableView {
     fn render(&mut self, area: Rect, buf: &mut Buffer, focus: bool) {
+        let mut title = " View ".to_string();
+        if let Some(filter) = &self.filter {
+            title.push_str(&format!("WHERE {} ", filter));
+        }
+        if !self.marked.is_empty() {
+            title.push_str(&format!("({} marked) ", self.marked.len()));
+        }
         let block = Block::bordered()
-            .title(" View ")
+            .title(title)
             .border_type(BorderType::Rounded)
             .padding(Padding::uniform(1))
             .border_style(if focus {
@@ -30,16 +160,40 @@ impl Component for TableView {
 
         match &self.items {
             Some(items) => {
+                let keyed =
+                    !self.marked.is_empty() || !self.edits.is_empty() || !self.deletions.is_empty();
+
+                let keys: Vec<Option<Key>> = (0..items.1.len())
+                    .map(|idx| if keyed { self.key(idx).ok() } else { None })
+                    .collect();
+                let styles: Vec<Style> = keys
+                    .iter()
+                    .map(|key| self.row_style(key.as_ref()))
+                    .collect();
+
                 let rows = items
                     .1
                     .iter()
-                    .map(|items| {
-                        Row::new(
-                            items
-                                .iter()
-                                .map(|item| item.as_str())
-                                .collect::<Vec<&str>>(),
-                        )
+                    .zip(keys.iter().zip(styles))
+                    .map(|(values, (key, style))| {
+                        Row::new(values.iter().zip(&items.0).map(|(value, column)| {
+                            let edit = key.as_ref().and_then(|key| {
+                                self.edits
+                                    .iter()
+                                    .rev()
+                                    .find(|(edited, name, _)| edited == key && name == column)
+                            });
+
+                            match edit {
+                                Some((_, _, new)) => Cell::from(new.as_str()).style(
+                                    Style::default()
+                                        .fg(Color::Yellow)
+                                        .add_modifier(Modifier::BOLD | Modifier::ITALIC),
+                                ),
+                                None => Cell::from(db::shown(value)),
+                            }
+                        }))
+                        .style(style)
                     })
                     .collect::<Vec<Row>>();
 
@@ -63,7 +217,8 @@ impl Component for TableView {
                         .bottom_margin(1),
                     )
                     .highlight_symbol(">> ")
-                    .row_highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+                    .row_highlight_style(Style::default().add_modifier(Modifier::BOLD))
+                    .cell_highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
                     area,
                     buf,
                     &mut self.state,
@@ -0,0 +1,81 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Single-line editable text with a cursor.
+#[derive(Clone, Default)]
+pub struct TextInput {
+    pub text: String,
+    /// Cursor position, in characters.
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+
+        Self {
+            cursor: text.chars().count(),
+            text,
+        }
+    }
+
+    fn offset(&self, cursor: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.text.len(), |(idx, _)| idx)
+    }
+
+    /// Applies an editing key to the text; returns whether it was one.
+    pub fn edit(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) => {
+                let offset = self.offset(self.cursor);
+                self.text.insert(offset, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let offset = self.offset(self.cursor);
+                self.text.remove(offset);
+            }
+            KeyCode::Delete if self.cursor < self.text.chars().count() => {
+                let offset = self.offset(self.cursor);
+                self.text.remove(offset);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.chars().count(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// The text with the cursor drawn in, scrolled to keep it within `width`.
+    pub fn line(&self, width: usize) -> Line<'static> {
+        let skip = self.cursor.saturating_sub(width.saturating_sub(1));
+        let before: String = self
+            .text
+            .chars()
+            .skip(skip)
+            .take(self.cursor - skip)
+            .collect();
+        let at: String = self
+            .text
+            .chars()
+            .nth(self.cursor)
+            .map_or(" ".into(), String::from);
+        let after: String = self.text.chars().skip(self.cursor + 1).collect();
+
+        Line::from(vec![
+            Span::raw(before),
+            Span::styled(at, Style::default().add_modifier(Modifier::REVERSED)),
+            Span::raw(after),
+        ])
+    }
+}